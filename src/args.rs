@@ -1,5 +1,11 @@
-use clap::{arg, Parser};
+#[cfg(feature = "cli")]
+use std::time::Duration;
 
+#[cfg(feature = "cli")]
+use clap::{arg, Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct BalanceArgs {
     #[arg(
@@ -8,8 +14,15 @@ pub struct BalanceArgs {
         help = "The address of the account to fetch the balance of"
     )]
     pub address: Option<String>,
+
+    #[arg(
+        long,
+        help = "Fetch the ORE price from CoinGecko to estimate the USD value of your balances"
+    )]
+    pub coingecko: bool,
 }
 
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct BenchmarkArgs {
     #[arg(
@@ -20,11 +33,57 @@ pub struct BenchmarkArgs {
         default_value = "1"
     )]
     pub cores: u64,
+
+    #[arg(
+        long,
+        short,
+        value_name = "SECONDS",
+        help = "How long to run the benchmark for",
+        default_value = "60"
+    )]
+    pub duration: u64,
+
+    #[arg(
+        long,
+        value_name = "DIFFICULTY",
+        help = "The minimum difficulty to estimate solve times against",
+        default_value = "8"
+    )]
+    pub min_difficulty: u32,
+
+    #[arg(
+        long,
+        help = "Benchmark every core count from 1 to the number of available cores"
+    )]
+    pub sweep: bool,
 }
 
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct BenchmarkThreadsArgs {
+    #[arg(
+        long,
+        value_name = "THREAD_COUNT",
+        help = "The largest number of threads to benchmark, counting up from 1",
+        default_value_t = num_cpus::get() as u64
+    )]
+    pub max_threads: u64,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "How long to hash at each thread count, such as 30s or 2m",
+        default_value = "30s",
+        value_parser = humantime::parse_duration
+    )]
+    pub duration_per_run: Duration,
+}
+
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct BussesArgs {}
 
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct ClaimArgs {
     #[arg(
@@ -40,20 +99,91 @@ pub struct ClaimArgs {
         help = "Wallet to receive claimed tokens."
     )]
     pub to: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "TOKEN_ACCOUNT_ADDRESS",
+        help = "Token account to receive claimed tokens. Defaults to your associated token account.",
+        conflicts_with = "to"
+    )]
+    pub destination: Option<String>,
 }
 
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct CloseArgs {}
 
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct CheckArgs {
+    #[arg(
+        long,
+        short,
+        value_name = "THREAD_COUNT",
+        help = "The number of cores to benchmark with",
+        default_value = "1"
+    )]
+    pub cores: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "The number of seconds to stop mining before the cutoff, to estimate the cutoff with",
+        default_value = "5"
+    )]
+    pub buffer_time: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "How long to benchmark hashpower for",
+        default_value = "5"
+    )]
+    pub benchmark_duration: u64,
+}
+
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct ConfigArgs {}
 
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct HistoryArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "SQLite database the passes were recorded to with mine --db"
+    )]
+    pub db: String,
+
+    #[arg(
+        long,
+        value_name = "DAYS",
+        help = "Number of days to summarize, including today",
+        default_value = "30"
+    )]
+    pub days: u64,
+
+    #[arg(long, help = "Print the summary as CSV")]
+    pub csv: bool,
+}
+
 #[cfg(feature = "admin")]
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct InitializeArgs {}
 
-#[derive(Parser, Debug)]
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug, Deserialize, Serialize)]
 pub struct MineArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "TOML or JSON file of mine options saved with save-config. Options passed on the command line take precedence."
+    )]
+    #[serde(skip)]
+    pub args_file: Option<String>,
+
     #[arg(
         long,
         short,
@@ -71,8 +201,561 @@ pub struct MineArgs {
         default_value = "5"
     )]
     pub buffer_time: u64,
+
+    #[arg(
+        long,
+        help = "Set the buffer time from recent transaction confirmation times"
+    )]
+    pub buffer_time_adaptive: bool,
+
+    #[arg(
+        long,
+        value_name = "PASSES",
+        help = "Number of recent confirmation times to adapt the buffer time to",
+        default_value = "5",
+        requires = "buffer_time_adaptive"
+    )]
+    pub latency_window: usize,
+
+    #[arg(
+        long,
+        value_name = "DIFFICULTY",
+        help = "Keep mining until a hash of at least this difficulty is found. Cannot be lower than the program minimum."
+    )]
+    pub min_difficulty: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "PERCENTILE",
+        help = "Submit before the cutoff only if the hash beats this percentile of the last 50 passes' difficulties",
+        value_parser = clap::value_parser!(u8).range(0..=100)
+    )]
+    pub difficulty_percentile: Option<u8>,
+
+    #[arg(
+        long,
+        value_name = "DIFFICULTY",
+        help = "Ring the bell and print a banner for new session difficulty records at or above this difficulty"
+    )]
+    pub alert_difficulty: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "MB",
+        help = "Reduce the number of cores so solver memory stays within this many megabytes"
+    )]
+    pub memory_limit: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "MB",
+        help = "Warn when the miner's resident memory grows beyond this many megabytes"
+    )]
+    pub max_rss_mb: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Time into a pass after which to stop holding out for --min-difficulty and submit any hash that meets the program's minimum",
+        default_value = "300"
+    )]
+    pub max_wait_secs: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Warn when the local clock is more than this many seconds from the cluster clock"
+    )]
+    pub max_clock_drift: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "ACTION",
+        help = "What to do when the local clock drifts past the max clock drift",
+        default_value = "warn",
+        requires = "max_clock_drift"
+    )]
+    pub max_clock_drift_action: ClockDriftAction,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "What to do when a pass only finds a solution at the program's minimum difficulty",
+        default_value = "submit"
+    )]
+    pub on_low_difficulty: LowDifficultyPolicy,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "How long past the cutoff to keep mining for a better solution with --on-low-difficulty wait",
+        default_value = "30s",
+        value_parser = humantime::parse_duration
+    )]
+    #[serde(with = "humantime_serde")]
+    pub low_difficulty_wait: Duration,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Re-fetch the proof this often during a pass, such as 10s, and start a new pass if its challenge changed",
+        value_parser = humantime::parse_duration
+    )]
+    #[serde(default, with = "humantime_serde")]
+    pub challenge_refresh_interval: Option<Duration>,
+
+    #[arg(
+        long,
+        help = "Fetch the clock and config for the next pass in the background as soon as a pass submits"
+    )]
+    pub prefetch_clock: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Restart a pass that does not complete within this many seconds"
+    )]
+    pub watchdog_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "File to touch after every successful pass, so a supervisor can restart a stalled miner"
+    )]
+    pub watchdog_file: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Rest after submitting until at least this many seconds have passed since the pass started"
+    )]
+    pub pass_interval: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "The number of seconds to cache bus balances for before refetching",
+        default_value = "30"
+    )]
+    pub bus_cache_ttl: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Reuse the fetched proof for this many seconds until a pass lands. Only safe when nothing else submits for this wallet"
+    )]
+    pub proof_cache_ttl: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "DIRECTORY",
+        help = "Directory of keypair files to mine with in parallel",
+        alias = "wallets-dir"
+    )]
+    pub wallets: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "THREADS",
+        help = "Give each wallet its own pool of this many threads, up to the available cores",
+        requires = "wallets"
+    )]
+    pub threads_per_wallet: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Filepath to a SQLite database to record mining passes in"
+    )]
+    pub db: Option<String>,
+
+    #[arg(
+        long,
+        help = "Resume the totals of the most recent session recorded in the database",
+        requires = "db"
+    )]
+    pub resume: bool,
+
+    #[arg(
+        long,
+        value_name = "SESSION_ID",
+        help = "Resume the totals of a session recorded in the database",
+        requires = "db",
+        conflicts_with = "resume"
+    )]
+    pub resume_session: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "CSV file to append a row to after every pass"
+    )]
+    pub log_file: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "MB",
+        help = "Rotate the log file once it grows past this size",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "log_file"
+    )]
+    pub log_rotate_size: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "FILES",
+        help = "Number of rotated log files to keep",
+        default_value = "5",
+        requires = "log_rotate_size"
+    )]
+    pub log_rotate_keep: usize,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Markdown file to write a session summary and pass history to when mining stops"
+    )]
+    pub export_md: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "CELSIUS",
+        help = "Halve the number of cores used while the CPU is hotter than this temperature"
+    )]
+    pub max_temp: Option<f32>,
+
+    #[arg(
+        long,
+        value_name = "SOL",
+        help = "Skip submitting a pass if its estimated transaction fee exceeds this amount"
+    )]
+    pub max_sol_per_pass: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Automatically claim your stake once the claim penalty window has elapsed"
+    )]
+    pub auto_claim: bool,
+
+    #[arg(
+        long,
+        value_name = "AMOUNT",
+        help = "The minimum stake to automatically claim",
+        default_value = "0.1",
+        requires = "auto_claim"
+    )]
+    pub min_claim_ore: f64,
+
+    #[arg(
+        long,
+        value_name = "MULTIPLIER",
+        help = "Claim during the penalty window, up to this fraction of the stake claimable without a penalty burn",
+        value_parser = parse_stake_threshold_multiplier,
+        requires = "auto_claim"
+    )]
+    pub stake_threshold_multiplier: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Automatically stake ORE in your wallet after each pass",
+        conflicts_with = "auto_claim"
+    )]
+    pub stake_auto: bool,
+
+    #[arg(
+        long,
+        value_name = "ORE",
+        help = "Minimum wallet balance of ORE to stake automatically",
+        default_value = "0.5",
+        requires = "stake_auto"
+    )]
+    pub min_stake_ore: f64,
+
+    #[arg(
+        long,
+        help = "Stake ORE in your wallet at the start of each pass",
+        conflicts_with_all = ["auto_claim", "stake_auto", "dry_run"]
+    )]
+    pub stake_before_mine: bool,
+
+    #[arg(
+        long,
+        value_name = "ORE",
+        help = "Minimum wallet balance of ORE to stake before each pass",
+        default_value = "0.5",
+        requires = "stake_before_mine"
+    )]
+    pub min_stake_amount: f64,
+
+    #[arg(
+        long,
+        value_name = "KEYPAIR_PATH",
+        help = "Funding wallet to top up the fee payer from when its balance runs low. Use prompt:// to enter a seed phrase and passphrase instead.",
+        conflicts_with = "dry_run"
+    )]
+    pub auto_topup: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SOL",
+        help = "Amount of SOL to transfer from the funding wallet on each top up",
+        default_value = "0.05",
+        requires = "auto_topup"
+    )]
+    pub topup_amount: f64,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "What to do when the fee payer's SOL balance is too low to submit",
+        default_value = "pause"
+    )]
+    pub on_insufficient_sol: InsufficientSolPolicy,
+
+    #[arg(
+        long,
+        value_name = "PORT",
+        help = "Port to serve Prometheus metrics on at /metrics"
+    )]
+    pub metrics_port: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "PORT",
+        help = "Port to serve the gRPC miner service on, for stats and remote control"
+    )]
+    pub grpc_port: Option<u16>,
+
+    #[arg(
+        long,
+        value_name = "WEBHOOK_URL",
+        help = "Discord webhook to post session summaries to"
+    )]
+    pub notify_discord: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PASSES",
+        help = "Number of passes between Discord and Telegram summaries",
+        default_value = "10"
+    )]
+    pub notify_interval: u64,
+
+    #[arg(
+        long,
+        value_name = "BOT_TOKEN",
+        help = "Telegram bot token to post notifications with. Prefer setting it in the config file, so it does not show up in process listings."
+    )]
+    pub notify_telegram: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "CHAT_ID",
+        help = "Telegram chat to post notifications to"
+    )]
+    pub notify_chat_id: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "URL_OR_ADDRESS",
+        help = "CoinGecko price url or Pyth price account to estimate USD earnings with"
+    )]
+    pub ore_price_feed: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "ALPHA",
+        help = "Weight of the latest pass in the estimated ORE per hour, between 0 and 1",
+        default_value = "0.3",
+        value_parser = parse_ema_alpha
+    )]
+    pub ema_alpha: f64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Time to wait for the current pass to finish after a shutdown signal",
+        default_value = "120"
+    )]
+    pub shutdown_timeout: u64,
+
+    #[arg(
+        long,
+        help = "Compute hashes without submitting any transactions",
+        conflicts_with_all = ["auto_claim", "stake_auto"]
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        help = "Skip the registration check at startup. Mining fails if the wallet is not registered",
+        conflicts_with = "force_register"
+    )]
+    pub skip_register: bool,
+
+    #[arg(
+        long,
+        help = "Close and reopen the proof account at startup, to recover from a corrupted proof. Requires an empty stake"
+    )]
+    pub force_register: bool,
+
+    #[arg(long, help = "Wait for the next epoch to start before mining")]
+    pub epoch_wait: bool,
+
+    #[arg(
+        long,
+        value_name = "PASSES",
+        help = "Exit after mining this many passes with each wallet"
+    )]
+    pub pass_limit: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PASSES",
+        help = "Start at most this many passes per hour with each wallet, waiting between passes if needed",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub max_passes_per_hour: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "CRON",
+        help = "Only mine during the UTC hours a cron expression fires in, such as \"0 0-6 * * *\""
+    )]
+    pub schedule: Option<String>,
+
+    #[arg(long, help = "Show live mining stats in a full-screen dashboard")]
+    pub tui: bool,
+
+    #[arg(long, help = "Skip the startup banner")]
+    pub quiet: bool,
+
+    #[arg(
+        long,
+        help = "Skip recomputing each solution's hash before submitting it. Useful for benchmarking."
+    )]
+    pub no_verify: bool,
+
+    #[arg(
+        long,
+        help = "Simulate each transaction first and skip submitting it if the simulation fails"
+    )]
+    pub simulate: bool,
+
+    #[arg(
+        long,
+        help = "Hash on rayon's global thread pool instead of spawning a thread per core",
+        conflicts_with = "threads_per_wallet"
+    )]
+    pub rayon: bool,
+
+    #[arg(
+        long,
+        help = "Allocate every thread's solver memory before the first pass and reuse it across passes"
+    )]
+    pub mem_prealloc: bool,
+
+    #[arg(long, help = "Pin each thread to a distinct physical core")]
+    pub pin_threads: bool,
+
+    #[arg(
+        long,
+        value_name = "PRIORITY",
+        help = "OS scheduling priority of the mining threads",
+        default_value = "normal"
+    )]
+    pub thread_priority: ThreadPriorityLevel,
+
+    #[arg(
+        long,
+        value_name = "GOVERNOR",
+        help = "Set every CPU's frequency scaling governor while mining, restoring it on exit. Linux only, requires root."
+    )]
+    pub cpu_governor: Option<CpuGovernor>,
+
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        help = "How threads split the nonce space: claim consecutive batches (linear), interleave nonces (strided), or hash up from a random nonce in their own slice (random)",
+        default_value = "linear"
+    )]
+    pub nonce_strategy: NonceStrategy,
+
+    #[arg(
+        long,
+        help = "Send transactions directly to the leader's TPU port, falling back to rpc",
+        conflicts_with = "dry_run"
+    )]
+    pub tpu_client: bool,
+
+    #[arg(
+        long,
+        help = "Submit transactions as bundles through the Jito block engine",
+        conflicts_with_all = ["dry_run", "tpu_client"]
+    )]
+    pub jito: bool,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "The Jito block engine to submit bundles to",
+        default_value = "https://mainnet.block-engine.jito.wtf",
+        requires = "jito"
+    )]
+    pub jito_url: String,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Tip to pay the Jito tip account with each bundle",
+        default_value = "1000",
+        requires = "jito"
+    )]
+    pub jito_tip_lamports: u64,
+
+    #[arg(
+        long,
+        value_name = "H/S",
+        help = "Adjust the number of cores between passes to stay near this hashrate"
+    )]
+    pub target_hashrate: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PROGRAM_ID:DATA:ACCOUNTS",
+        help = "Instruction to append to every mining transaction, as a program id, base58 data and comma-separated pubkey[:signer][:writable] accounts. May be repeated."
+    )]
+    pub extra_instruction: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct PoolArgs {
+    #[arg(
+        long,
+        value_name = "POOL_URL",
+        help = "Websocket url of the pool server, e.g. wss://pool.example.com"
+    )]
+    pub pool_url: String,
+
+    #[arg(
+        long,
+        value_name = "API_KEY",
+        help = "API key to authenticate with the pool server"
+    )]
+    pub pool_api_key: Option<String>,
+
+    #[arg(
+        long,
+        short,
+        value_name = "CORES_COUNT",
+        help = "The number of CPU cores to allocate to mining",
+        default_value = "1"
+    )]
+    pub cores: u64,
 }
 
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct ProofArgs {
     #[arg(
@@ -83,18 +766,40 @@ pub struct ProofArgs {
     pub address: Option<String>,
 }
 
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct RegisterArgs {}
+
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct RewardsArgs {}
 
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct StakeArgs {
     #[arg(
         long,
         value_name = "AMOUNT",
-        help = "The amount of Ore to stake. Defaults to max."
+        help = "The amount of Ore to stake. Defaults to max.",
+        conflicts_with = "all"
     )]
     pub amount: Option<f64>,
 
+    #[arg(
+        long,
+        help = "Stake the entire token account balance, less --keep-liquid"
+    )]
+    pub all: bool,
+
+    #[arg(
+        long,
+        value_name = "AMOUNT",
+        help = "The amount of Ore to leave in the token account when staking the max",
+        default_value = "0",
+        conflicts_with = "amount"
+    )]
+    pub keep_liquid: f64,
+
     #[arg(
         long,
         value_name = "TOKEN_ACCOUNT_ADDRESS",
@@ -103,6 +808,7 @@ pub struct StakeArgs {
     pub sender: Option<String>,
 }
 
+#[cfg(feature = "cli")]
 #[derive(Parser, Debug)]
 pub struct UpgradeArgs {
     #[arg(
@@ -112,3 +818,255 @@ pub struct UpgradeArgs {
     )]
     pub amount: Option<f64>,
 }
+
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct SaveConfigArgs {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "File to write the mine options to, as JSON if it ends in .json and TOML otherwise"
+    )]
+    pub output: String,
+
+    #[command(flatten)]
+    pub mine: MineArgs,
+}
+
+/// Options for testing the miner, only available with the `test-utils` feature.
+#[cfg(feature = "test-utils")]
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct TestUtilsArgs {
+    #[arg(
+        long,
+        value_name = "MILLISECONDS",
+        help = "Delay every rpc call by this long, to test against a slow network",
+        global = true
+    )]
+    pub simulate_network_latency: Option<u64>,
+}
+
+#[cfg(not(feature = "test-utils"))]
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct TestUtilsArgs {}
+
+#[cfg(feature = "cli")]
+#[derive(Parser, Debug)]
+pub struct VerifyArgs {
+    #[arg(
+        long,
+        value_name = "SIGNATURE",
+        help = "Signature of the transaction that submitted the solution"
+    )]
+    pub signature: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Network {
+    MainnetBeta,
+    Devnet,
+    Testnet,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => tracing::Level::TRACE,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum LogFormat {
+    Human,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum LowDifficultyPolicy {
+    /// Discard the solution and start the next pass.
+    Skip,
+    /// Keep mining past the cutoff for a better solution.
+    Wait,
+    /// Submit the solution.
+    Submit,
+}
+
+impl std::fmt::Display for LowDifficultyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = match self {
+            LowDifficultyPolicy::Skip => "skip",
+            LowDifficultyPolicy::Wait => "wait",
+            LowDifficultyPolicy::Submit => "submit",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum ClockDriftAction {
+    /// Print a warning and keep mining.
+    Warn,
+    /// Stop mining.
+    Exit,
+}
+
+impl std::fmt::Display for ClockDriftAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self {
+            ClockDriftAction::Warn => "warn",
+            ClockDriftAction::Exit => "exit",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum InsufficientSolPolicy {
+    /// Wait a minute before the next pass.
+    Pause,
+    /// Print the session totals and exit with code 1.
+    Exit,
+    /// Start the next pass right away.
+    Skip,
+}
+
+impl std::fmt::Display for InsufficientSolPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = match self {
+            InsufficientSolPolicy::Pause => "pause",
+            InsufficientSolPolicy::Exit => "exit",
+            InsufficientSolPolicy::Skip => "skip",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadPriorityLevel {
+    High,
+    Normal,
+    Low,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum CpuGovernor {
+    Performance,
+    Powersave,
+}
+
+impl std::fmt::Display for CpuGovernor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let governor = match self {
+            CpuGovernor::Performance => "performance",
+            CpuGovernor::Powersave => "powersave",
+        };
+        write!(f, "{}", governor)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum NonceStrategy {
+    /// Threads claim consecutive batches from a shared counter.
+    Linear,
+    /// Thread `i` of `n` hashes nonces `i`, `i + n`, `i + 2n`, ...
+    Strided,
+    /// Each thread hashes upward from a random nonce within its own slice.
+    Random,
+}
+
+impl std::fmt::Display for NonceStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let strategy = match self {
+            NonceStrategy::Linear => "linear",
+            NonceStrategy::Strided => "strided",
+            NonceStrategy::Random => "random",
+        };
+        write!(f, "{}", strategy)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum PriorityFeeMode {
+    Fixed,
+    Dynamic,
+}
+
+/// Smallest balance that still covers a transaction's base fee.
+const MIN_TX_FEE_SOL: f64 = 0.000005;
+
+/// Parses a minimum SOL balance, which must cover at least one transaction fee.
+pub fn parse_min_sol_balance(value: &str) -> Result<f64, String> {
+    let sol: f64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is not a number", value))?;
+    if sol.lt(&MIN_TX_FEE_SOL) {
+        return Err(format!(
+            "must be at least {} SOL to cover a transaction fee",
+            MIN_TX_FEE_SOL
+        ));
+    }
+    Ok(sol)
+}
+
+/// Parses a moving average weight, which must be above zero and at most one.
+pub fn parse_ema_alpha(value: &str) -> Result<f64, String> {
+    let alpha: f64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is not a number", value))?;
+    if alpha.le(&0.0) || alpha.gt(&1.0) {
+        return Err("must be above 0 and at most 1".to_string());
+    }
+    Ok(alpha)
+}
+
+/// Parses a claim multiplier, which must be above zero and at most one.
+pub fn parse_stake_threshold_multiplier(value: &str) -> Result<f64, String> {
+    let multiplier: f64 = value
+        .parse()
+        .map_err(|_| format!("`{}` is not a number", value))?;
+    if multiplier.le(&0.0) || multiplier.gt(&1.0) {
+        return Err("must be above 0 and at most 1".to_string());
+    }
+    Ok(multiplier)
+}
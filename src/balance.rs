@@ -1,45 +1,132 @@
 use std::str::FromStr;
 
-use solana_program::pubkey::Pubkey;
-use solana_sdk::signature::Signer;
+use ore_api::{consts::MINT_ADDRESS, state::Proof};
+use ore_utils::AccountDeserialize;
+use serde_json::{json, Value};
+use solana_program::{native_token::lamports_to_sol, program_pack::Pack, pubkey::Pubkey, sysvar};
+use solana_sdk::{clock::Clock, signature::Signer};
+use tracing::info;
 
 use crate::{
     args::BalanceArgs,
-    utils::{amount_u64_to_string, get_proof_with_authority},
+    claim::{claim_penalty, ONE_DAY},
+    error::MineError,
+    output::print_json_event,
+    utils::{amount_u64_to_f64, amount_u64_to_string, proof_pubkey},
     Miner,
 };
 
+const COINGECKO_PRICE_URL: &str =
+    "https://api.coingecko.com/api/v3/simple/price?ids=ore&vs_currencies=usd";
+
 impl Miner {
-    pub async fn balance(&self, args: BalanceArgs) {
-        let signer = self.signer();
-        let address = if let Some(address) = args.address {
-            if let Ok(address) = Pubkey::from_str(&address) {
-                address
-            } else {
-                println!("Invalid address: {:?}", address);
-                return;
-            }
-        } else {
-            signer.pubkey()
+    pub async fn balance(&self, args: BalanceArgs) -> Result<(), MineError> {
+        let address = match args.address {
+            Some(address) => Pubkey::from_str(&address)
+                .map_err(|_| MineError::InvalidArgs(format!("Invalid address: {:?}", address)))?,
+            None => self.try_signer()?.pubkey(),
         };
-        let proof = get_proof_with_authority(&self.rpc_client, address).await;
-        let token_account_address = spl_associated_token_account::get_associated_token_address(
-            &address,
-            &ore_api::consts::MINT_ADDRESS,
-        );
-        let token_balance = if let Ok(Some(token_account)) = self
+
+        // Fetch every account in a single round trip
+        let token_account_address =
+            spl_associated_token_account::get_associated_token_address(&address, &MINT_ADDRESS);
+        let accounts = self
             .rpc_client
-            .get_token_account(&token_account_address)
-            .await
-        {
-            token_account.token_amount.ui_amount_string
+            .get_multiple_accounts(&[
+                address,
+                proof_pubkey(address),
+                token_account_address,
+                sysvar::clock::ID,
+            ])
+            .await?;
+
+        // Parse accounts
+        let sol_balance = accounts[0].as_ref().map_or(0, |account| account.lamports);
+        let proof = accounts[1]
+            .as_ref()
+            .and_then(|account| Proof::try_from_bytes(&account.data).ok().copied());
+        let token_balance = accounts[2]
+            .as_ref()
+            .and_then(|account| spl_token::state::Account::unpack(&account.data).ok())
+            .map_or(0, |token_account| token_account.amount);
+        let clock = accounts[3]
+            .as_ref()
+            .and_then(|account| bincode::deserialize::<Clock>(&account.data).ok());
+        let stake = proof.map_or(0, |proof| proof.balance);
+
+        // Calculate penalty for claiming now
+        let (since_last_stake, penalty) = match (proof, clock) {
+            (Some(proof), Some(clock)) if proof.last_stake_at.gt(&0) => (
+                Some(clock.unix_timestamp.saturating_sub(proof.last_stake_at)),
+                claim_penalty(&proof, proof.balance, clock.unix_timestamp),
+            ),
+            _ => (None, 0),
+        };
+
+        // Fetch price
+        let price = if args.coingecko {
+            fetch_ore_price().await
         } else {
-            "0".to_string()
+            None
         };
-        println!(
-            "Balance: {} ORE\nStake: {} ORE",
-            token_balance,
-            amount_u64_to_string(proof.balance)
-        )
+        let total_value =
+            price.map(|price| amount_u64_to_f64(stake.saturating_add(token_balance)) * price);
+
+        if self.is_json() {
+            print_json_event(
+                "balance",
+                json!({
+                    "wallet": address.to_string(),
+                    "sol_balance": lamports_to_sol(sol_balance),
+                    "staked_balance": amount_u64_to_f64(stake),
+                    "token_balance": amount_u64_to_f64(token_balance),
+                    "seconds_since_last_stake": since_last_stake,
+                    "claim_penalty": amount_u64_to_f64(penalty),
+                    "price_usd": price,
+                    "total_value_usd": total_value,
+                }),
+            );
+            return Ok(());
+        }
+        info!("SOL: {} SOL", lamports_to_sol(sol_balance));
+        info!("Balance: {} ORE", amount_u64_to_string(token_balance));
+        info!("Stake: {} ORE", amount_u64_to_string(stake));
+        if let Some(since_last_stake) = since_last_stake {
+            info!("Last stake: {} ago", format_duration(since_last_stake));
+        }
+        if penalty.gt(&0) {
+            info!(
+                "Penalty: {} ORE burned if claimed now ({} until penalty free)",
+                amount_u64_to_string(penalty),
+                format_duration(ONE_DAY.saturating_sub(since_last_stake.unwrap_or(0)))
+            );
+        } else {
+            info!("Penalty: none");
+        }
+        if args.coingecko {
+            match (price, total_value) {
+                (Some(price), Some(total_value)) => {
+                    info!("Price: ${:.4}", price);
+                    info!("Value: ${:.2}", total_value);
+                }
+                _ => info!("Price: unavailable"),
+            }
+        }
+        Ok(())
     }
 }
+
+async fn fetch_ore_price() -> Option<f64> {
+    let response: Value = reqwest::get(COINGECKO_PRICE_URL)
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()?;
+    response["ore"]["usd"].as_f64()
+}
+
+fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+}
@@ -1,80 +1,190 @@
-use std::{sync::Arc, time::Instant};
+use serde_json::json;
+use tracing::info;
 
-use drillx::equix;
-use solana_rpc_client::spinner;
+use crate::{
+    args::{BenchmarkArgs, BenchmarkThreadsArgs, ThreadPriorityLevel},
+    error::MineError,
+    output::print_json_event,
+    Miner,
+};
 
-use crate::{args::BenchmarkArgs, Miner};
-
-const TEST_DURATION: i64 = 30;
+struct BenchmarkResult {
+    cores: u64,
+    hashrate: u64,
+    best_difficulty: u32,
+}
 
 impl Miner {
-    pub async fn benchmark(&self, args: BenchmarkArgs) {
-        // Check num threads
-        self.check_num_cores(args.cores);
+    pub async fn benchmark(&self, args: BenchmarkArgs) -> Result<(), MineError> {
+        // Pick core counts to benchmark
+        let core_counts: Vec<u64> = if args.sweep {
+            (1..=num_cpus::get() as u64).collect()
+        } else {
+            self.check_num_cores(args.cores);
+            vec![args.cores]
+        };
 
-        // Dispatch job to each thread
+        // Hash a synthetic challenge, so no rpc connection is needed
         let challenge = [0; 32];
-        let progress_bar = Arc::new(spinner::new_progress_bar());
-        progress_bar.set_message(format!(
-            "Benchmarking. This will take {} sec...",
-            TEST_DURATION
-        ));
-        let core_ids = core_affinity::get_core_ids().unwrap();
-        let handles: Vec<_> = core_ids
-            .into_iter()
-            .map(|i| {
-                std::thread::spawn({
-                    move || {
-                        let timer = Instant::now();
-                        let first_nonce = u64::MAX
-                            .saturating_div(args.cores)
-                            .saturating_mul(i.id as u64);
-                        let mut nonce = first_nonce;
-                        let mut memory = equix::SolverMemory::new();
-                        loop {
-                            // Return if core should not be used
-                            if (i.id as u64).ge(&args.cores) {
-                                return 0;
-                            }
-
-                            // Pin to core
-                            let _ = core_affinity::set_for_current(i);
-
-                            // Create hash
-                            let _hx = drillx::hash_with_memory(
-                                &mut memory,
-                                &challenge,
-                                &nonce.to_le_bytes(),
-                            );
-
-                            // Increment nonce
-                            nonce += 1;
+        let mut results = vec![];
+        for cores in core_counts {
+            if !self.is_json() {
+                info!(
+                    "Benchmarking {} core(s). This will take {} sec...",
+                    cores, args.duration
+                );
+            }
+            let best_hash = Self::find_hash_par(
+                challenge,
+                args.duration,
+                cores,
+                0,
+                None,
+                None,
+                false,
+                ThreadPriorityLevel::Normal,
+            )
+            .await;
+            let result = BenchmarkResult {
+                cores,
+                hashrate: best_hash.hashes.saturating_div(args.duration.max(1)),
+                best_difficulty: best_hash.difficulty,
+            };
+            self.print_benchmark_result(&result, args.min_difficulty);
+            results.push(result);
+        }
 
-                            // Exit if time has elapsed
-                            if (timer.elapsed().as_secs() as i64).ge(&TEST_DURATION) {
-                                break;
-                            }
-                        }
+        // Print sweep summary
+        if args.sweep && !self.is_json() {
+            info!(
+                "\n{:>6} {:>12} {:>16} {:>14}",
+                "Cores", "H/sec", "Best difficulty", "Est. solve"
+            );
+            for result in results.iter() {
+                info!(
+                    "{:>6} {:>12} {:>16} {:>14}",
+                    result.cores,
+                    result.hashrate,
+                    result.best_difficulty,
+                    format_solve_time(estimated_solve_time(result.hashrate, args.min_difficulty)),
+                );
+            }
+            if let Some(best) = results.iter().max_by_key(|result| result.hashrate) {
+                info!(
+                    "\nFastest: {} core(s) at {} H/sec",
+                    best.cores, best.hashrate
+                );
+            }
+        }
+        Ok(())
+    }
 
-                        // Return hash count
-                        nonce - first_nonce
-                    }
-                })
-            })
-            .collect();
+    pub async fn benchmark_threads(&self, args: BenchmarkThreadsArgs) -> Result<(), MineError> {
+        // Hash a synthetic challenge at each thread count, so runs are comparable
+        let challenge = [0; 32];
+        let duration = args.duration_per_run.as_secs().max(1);
+        let mut results = vec![];
+        for threads in 1..=args.max_threads.max(1) {
+            if !self.is_json() {
+                info!(
+                    "Benchmarking {} thread(s). This will take {} sec...",
+                    threads, duration
+                );
+            }
+            let best_hash = Self::find_hash_par(
+                challenge,
+                duration,
+                threads,
+                0,
+                None,
+                None,
+                false,
+                ThreadPriorityLevel::Normal,
+            )
+            .await;
+            results.push(BenchmarkResult {
+                cores: threads,
+                hashrate: best_hash.hashes.saturating_div(duration),
+                best_difficulty: best_hash.difficulty,
+            });
+        }
 
-        // Join handles and return best nonce
-        let mut total_nonces = 0;
-        for h in handles {
-            if let Ok(count) = h.join() {
-                total_nonces += count;
+        // Compare each run against perfect scaling of the single thread run
+        let single_thread_hashrate = results.first().map_or(0, |result| result.hashrate);
+        let efficiency = |result: &BenchmarkResult| {
+            if single_thread_hashrate.eq(&0) {
+                return 0.0;
             }
+            result.hashrate as f64 / (result.cores * single_thread_hashrate) as f64 * 100.0
+        };
+        if self.is_json() {
+            print_json_event(
+                "benchmark_threads",
+                json!({
+                    "duration_per_run": duration,
+                    "runs": results
+                        .iter()
+                        .map(|result| json!({
+                            "threads": result.cores,
+                            "hashrate": result.hashrate,
+                            "best_difficulty": result.best_difficulty,
+                            "efficiency_percent": efficiency(result),
+                        }))
+                        .collect::<Vec<_>>(),
+                }),
+            );
+            return Ok(());
+        }
+        info!("\n| threads | H/s | best_difficulty | efficiency_percent |");
+        info!("|---|---|---|---|");
+        for result in results.iter() {
+            info!(
+                "| {} | {} | {} | {:.1} |",
+                result.cores,
+                result.hashrate,
+                result.best_difficulty,
+                efficiency(result)
+            );
+        }
+        Ok(())
+    }
+
+    fn print_benchmark_result(&self, result: &BenchmarkResult, min_difficulty: u32) {
+        let solve_time = estimated_solve_time(result.hashrate, min_difficulty);
+        if self.is_json() {
+            print_json_event(
+                "benchmark",
+                json!({
+                    "cores": result.cores,
+                    "hashrate": result.hashrate,
+                    "best_difficulty": result.best_difficulty,
+                    "min_difficulty": min_difficulty,
+                    "estimated_solve_seconds": solve_time,
+                }),
+            );
+        } else {
+            info!("  Hashpower: {} H/sec", result.hashrate);
+            info!("  Best difficulty: {}", result.best_difficulty);
+            info!(
+                "  Est. time to difficulty {}: {}",
+                min_difficulty,
+                format_solve_time(solve_time)
+            );
         }
+    }
+}
+
+/// Expected seconds to find a hash with the given number of leading zero bits.
+fn estimated_solve_time(hashrate: u64, difficulty: u32) -> Option<f64> {
+    if hashrate.eq(&0) {
+        return None;
+    }
+    Some(2f64.powi(difficulty as i32) / hashrate as f64)
+}
 
-        // Update log
-        progress_bar.finish_with_message(format!(
-            "Hashpower: {} H/sec",
-            total_nonces.saturating_div(TEST_DURATION as u64),
-        ));
+fn format_solve_time(seconds: Option<f64>) -> String {
+    match seconds {
+        Some(seconds) => format!("{:.2} sec", seconds),
+        None => "-".to_string(),
     }
 }
@@ -0,0 +1,54 @@
+use ore_cli::utils::amount_u64_to_f64;
+use serde_json::{json, Value};
+use tiny_http::{Header, Response, Server};
+
+use crate::state::{PoolState, SharedState};
+
+/// Serves the share accounting api on a background thread.
+///
+/// `GET /stats` returns pool totals, `GET /shares` the shares in the PPLNS window, and
+/// `GET /balances` or `GET /balances/<wallet>` the ORE credited to miners.
+pub fn serve_api(port: u16, state: SharedState) -> Result<(), String> {
+    let server = Server::http(("0.0.0.0", port)).map_err(|err| err.to_string())?;
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let body = route(request.url(), &state.lock().unwrap());
+            let response = match body {
+                Some(body) => Response::from_string(body.to_string()).with_header(
+                    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
+                ),
+                None => Response::from_string("Not found").with_status_code(404),
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
+
+fn route(url: &str, state: &PoolState) -> Option<Value> {
+    match url.trim_end_matches('/') {
+        "/stats" => Some(json!({
+            "miners": state.num_clients(),
+            "rounds": state.stats.rounds,
+            "shares": state.stats.shares,
+            "hashrate": state.stats.hashrate,
+            "last_difficulty": state.stats.last_difficulty,
+            "rewards": amount_u64_to_f64(state.stats.rewards),
+        })),
+        "/shares" => Some(json!(state.pplns.shares())),
+        "/balances" => Some(Value::Object(
+            state
+                .pplns
+                .balances()
+                .iter()
+                .map(|(wallet, balance)| (wallet.clone(), json!(amount_u64_to_f64(*balance))))
+                .collect(),
+        )),
+        url => url.strip_prefix("/balances/").map(|wallet| {
+            json!({
+                "wallet": wallet,
+                "balance": amount_u64_to_f64(state.pplns.balance(wallet)),
+            })
+        }),
+    }
+}
@@ -0,0 +1,166 @@
+mod api;
+mod pplns;
+mod round;
+mod state;
+mod ws;
+
+use std::sync::{Arc, Mutex};
+
+use clap::Parser;
+use ore_cli::{
+    args::{LogFormat, LogLevel},
+    builder::MinerBuilder,
+    logging,
+};
+use solana_sdk::signature::{read_keypair_file, Signer};
+use tracing::{error, info};
+
+use crate::state::PoolState;
+
+#[derive(Parser, Debug)]
+#[command(
+    about = "Run an ORE mining pool that splits each challenge across connected miners",
+    version
+)]
+struct Args {
+    #[arg(
+        long,
+        value_name = "NETWORK_URL",
+        help = "Network address of your RPC provider"
+    )]
+    rpc: String,
+
+    #[arg(
+        long,
+        value_name = "KEYPAIR_FILEPATH",
+        help = "Filepath to the keypair of the pool wallet, which owns the proof account"
+    )]
+    keypair: String,
+
+    #[arg(
+        long,
+        value_name = "PORT",
+        help = "Port to accept miner websocket connections on",
+        default_value = "8080"
+    )]
+    port: u16,
+
+    #[arg(
+        long,
+        value_name = "PORT",
+        help = "Port to serve the share accounting api on",
+        default_value = "8081"
+    )]
+    api_port: u16,
+
+    #[arg(
+        long,
+        value_name = "API_KEY",
+        help = "Only accept miners that authenticate with this API key"
+    )]
+    api_key: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "The number seconds before the deadline to stop collecting shares and submit",
+        default_value = "5"
+    )]
+    buffer_time: u64,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "The number of seconds to cache bus balances for before refetching",
+        default_value = "30"
+    )]
+    bus_cache_ttl: u64,
+
+    #[arg(
+        long,
+        value_name = "SHARES",
+        help = "Number of most recent shares each reward is split across",
+        default_value = "1000",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pplns_window: u64,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Price to pay for compute units when submitting"
+    )]
+    priority_fee: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        help = "Minimum severity of messages to print",
+        default_value = "info"
+    )]
+    log_level: LogLevel,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help = "Format to print log messages in",
+        default_value = "human"
+    )]
+    log_format: LogFormat,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+    logging::init(args.log_level, args.log_format, false);
+
+    // Build a miner for the pool wallet
+    let keypair = read_keypair_file(&args.keypair).unwrap_or_else(|err| {
+        error!("No keypair found at {}: {}", args.keypair, err);
+        std::process::exit(1);
+    });
+    let mut builder = MinerBuilder::new().rpc_url(args.rpc).keypair(keypair);
+    if let Some(priority_fee) = args.priority_fee {
+        builder = builder.priority_fee(priority_fee);
+    }
+    let miner = builder.build().unwrap_or_else(|err| {
+        error!("{}", err);
+        std::process::exit(1);
+    });
+
+    // Register the pool wallet
+    let (signer, fee_payer) = match miner
+        .try_signer()
+        .and_then(|signer| miner.try_fee_payer().map(|fee_payer| (signer, fee_payer)))
+    {
+        Ok(signers) => signers,
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    if let Err(err) = miner.open(signer.as_ref(), fee_payer.as_ref(), false).await {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+
+    // Accept miners and serve the api
+    let state = Arc::new(Mutex::new(PoolState::new(args.pplns_window as usize)));
+    if let Err(err) = api::serve_api(args.api_port, state.clone()) {
+        error!("Could not serve the api on port {}: {}", args.api_port, err);
+        std::process::exit(1);
+    }
+    if let Err(err) = ws::serve_miners(args.port, args.api_key, state.clone()).await {
+        error!("Could not listen for miners on port {}: {}", args.port, err);
+        std::process::exit(1);
+    }
+    info!(
+        "Pool {} accepting miners on port {}, api on port {}",
+        signer.pubkey(),
+        args.port,
+        args.api_port
+    );
+
+    // Mine rounds until stopped
+    round::run(&miner, args.buffer_time, args.bus_cache_ttl, &state).await;
+}
@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use serde::Serialize;
+
+/// A share accepted from a miner.
+#[derive(Clone, Debug, Serialize)]
+pub struct Share {
+    pub wallet: String,
+    pub difficulty: u32,
+    pub timestamp: i64,
+}
+
+/// Share weights are scaled so the hardest share in the window weighs 2^63, leaving room to
+/// multiply by a reward without overflowing.
+const MAX_WEIGHT_BITS: u32 = 63;
+
+/// Pay-per-last-N-shares accounting. Each reward is split across the most recent shares,
+/// in proportion to the work behind them, and credited to the wallets that submitted them.
+pub struct Pplns {
+    window: usize,
+    shares: VecDeque<Share>,
+    balances: BTreeMap<String, u64>,
+}
+
+impl Pplns {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            shares: VecDeque::with_capacity(window),
+            balances: BTreeMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, wallet: String, difficulty: u32) {
+        self.shares.push_back(Share {
+            wallet,
+            difficulty,
+            timestamp: chrono::Utc::now().timestamp(),
+        });
+        while self.shares.len().gt(&self.window) {
+            self.shares.pop_front();
+        }
+    }
+
+    /// Credits the reward to the wallets in the window, returning what each one earned.
+    /// Rounding dust stays with the pool.
+    pub fn distribute(&mut self, reward: u64) -> BTreeMap<String, u64> {
+        let mut payouts = BTreeMap::new();
+        let max_difficulty = self.shares.iter().map(|s| s.difficulty).max().unwrap_or(0);
+        let total: u128 = self
+            .shares
+            .iter()
+            .map(|s| share_weight(s.difficulty, max_difficulty))
+            .sum();
+        if total.eq(&0) {
+            return payouts;
+        }
+        for share in &self.shares {
+            let weight = share_weight(share.difficulty, max_difficulty);
+            let amount = (reward as u128 * weight / total) as u64;
+            *payouts.entry(share.wallet.clone()).or_insert(0) += amount;
+        }
+        for (wallet, amount) in &payouts {
+            *self.balances.entry(wallet.clone()).or_insert(0) += amount;
+        }
+        payouts
+    }
+
+    pub fn shares(&self) -> &VecDeque<Share> {
+        &self.shares
+    }
+
+    pub fn balances(&self) -> &BTreeMap<String, u64> {
+        &self.balances
+    }
+
+    pub fn balance(&self, wallet: &str) -> u64 {
+        self.balances.get(wallet).copied().unwrap_or(0)
+    }
+}
+
+/// Returns the work behind a share relative to the hardest share in the window. Work doubles
+/// with each bit of difficulty, so shares more than 63 bits easier than the hardest one count
+/// for nothing.
+fn share_weight(difficulty: u32, max_difficulty: u32) -> u128 {
+    let below = max_difficulty.saturating_sub(difficulty);
+    if below.gt(&MAX_WEIGHT_BITS) {
+        return 0;
+    }
+    1u128 << (MAX_WEIGHT_BITS - below)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_equal_shares_evenly() {
+        let mut pplns = Pplns::new(10);
+        pplns.record("a".to_string(), 20);
+        pplns.record("b".to_string(), 20);
+        let payouts = pplns.distribute(1_000);
+        assert_eq!(payouts["a"], 500);
+        assert_eq!(payouts["b"], 500);
+    }
+
+    #[test]
+    fn weights_shares_by_work() {
+        let mut pplns = Pplns::new(10);
+        pplns.record("hard".to_string(), 30);
+        pplns.record("easy".to_string(), 20);
+        let payouts = pplns.distribute(1_025_000);
+        assert_eq!(payouts["hard"], 1_024_000);
+        assert_eq!(payouts["easy"], 1_000);
+    }
+
+    #[test]
+    fn credits_balances_across_rewards() {
+        let mut pplns = Pplns::new(10);
+        pplns.record("a".to_string(), 20);
+        pplns.distribute(100);
+        pplns.distribute(100);
+        assert_eq!(pplns.balance("a"), 200);
+        assert_eq!(pplns.balance("b"), 0);
+    }
+
+    #[test]
+    fn keeps_only_the_window() {
+        let mut pplns = Pplns::new(2);
+        pplns.record("a".to_string(), 20);
+        pplns.record("b".to_string(), 20);
+        pplns.record("c".to_string(), 20);
+        let payouts = pplns.distribute(100);
+        assert!(!payouts.contains_key("a"));
+        assert_eq!(payouts["b"], 50);
+        assert_eq!(payouts["c"], 50);
+    }
+
+    #[test]
+    fn never_pays_out_more_than_the_reward() {
+        let mut pplns = Pplns::new(10);
+        for (wallet, difficulty) in [("a", 17), ("b", 23), ("c", 23), ("d", 40)] {
+            pplns.record(wallet.to_string(), difficulty);
+        }
+        let payouts = pplns.distribute(u64::MAX);
+        let paid = payouts
+            .values()
+            .fold(0u128, |acc, amount| acc + *amount as u128);
+        assert!(paid.le(&(u64::MAX as u128)));
+    }
+
+    #[test]
+    fn pays_nothing_without_shares() {
+        let mut pplns = Pplns::new(10);
+        assert!(pplns.distribute(100).is_empty());
+    }
+}
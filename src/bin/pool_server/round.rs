@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+
+use colored::*;
+use ore_cli::{
+    bus_selector::BusSelector,
+    error::MineError,
+    utils::{amount_u64_to_string, get_clock, get_config, get_proof_with_authority, proof_pubkey},
+    Miner,
+};
+use solana_sdk::signature::Signer;
+use tracing::{error, info, warn};
+
+use crate::state::SharedState;
+
+/// Time past the cutoff to wait for shares still in flight.
+const SHARE_GRACE_PERIOD: Duration = Duration::from_secs(3);
+
+/// Time to wait before starting a round again after an rpc error.
+const RPC_ERROR_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Mines one round per challenge: hands out the challenge, collects shares until the cutoff,
+/// submits the best one and splits the reward across the window.
+pub async fn run(miner: &Miner, buffer_time: u64, bus_cache_ttl: u64, state: &SharedState) {
+    let signer = match miner.try_signer() {
+        Ok(signer) => signer,
+        Err(err) => {
+            error!("{}", err);
+            return;
+        }
+    };
+    let mut bus_selector = BusSelector::new(Duration::from_secs(bus_cache_ttl));
+    loop {
+        // Wait for miners to connect
+        let num_clients = state.lock().unwrap().num_clients();
+        if num_clients.eq(&0) {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+
+        // Start round
+        let accounts = async {
+            Ok::<_, MineError>((
+                get_proof_with_authority(&miner.rpc_client, signer.pubkey()).await?,
+                get_config(&miner.rpc_client).await?,
+                get_clock(&miner.rpc_client).await?,
+            ))
+        };
+        let (proof, config, clock) = match accounts.await {
+            Ok(accounts) => accounts,
+            Err(err) => {
+                error!("Failed to start round: {}", err);
+                tokio::time::sleep(RPC_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        let cutoff = miner.get_cutoff(proof, buffer_time, &clock);
+        state
+            .lock()
+            .unwrap()
+            .start_round(proof.challenge, cutoff, config.min_difficulty as u32);
+        info!(
+            "Round started with {} miner(s), {} sec to mine",
+            num_clients, cutoff
+        );
+
+        // Collect shares until every miner has submitted or time runs out
+        let deadline = Instant::now() + Duration::from_secs(cutoff) + SHARE_GRACE_PERIOD;
+        while Instant::now().lt(&deadline) && !state.lock().unwrap().all_shares_in() {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        let Some(round) = state.lock().unwrap().end_round() else {
+            continue;
+        };
+        let Some(best) = round.best else {
+            warn!("No valid shares this round");
+            continue;
+        };
+
+        // Submit most difficult share, to a bus weighted by its remaining rewards
+        bus_selector.refresh(&miner.rpc_client).await;
+        let bus = bus_selector.select();
+        let ixs = [
+            ore_api::instruction::auth(proof_pubkey(signer.pubkey())),
+            ore_api::instruction::mine(signer.pubkey(), signer.pubkey(), bus, best.solution),
+        ];
+        if let Err(err) = miner
+            .send_and_confirm(
+                &ixs,
+                miner.compute_budget(500_000),
+                false,
+                miner.retry_policy,
+            )
+            .await
+        {
+            error!("Failed to submit difficulty {}: {}", best.difficulty, err);
+            continue;
+        }
+
+        // Split the reward across the window
+        let new_proof = match get_proof_with_authority(&miner.rpc_client, signer.pubkey()).await {
+            Ok(proof) => proof,
+            Err(err) => {
+                error!("Failed to read the reward for the round: {}", err);
+                continue;
+            }
+        };
+        let reward = new_proof.balance.saturating_sub(proof.balance);
+        let mut state = state.lock().unwrap();
+        let payouts = state.pplns.distribute(reward);
+        state.stats.rewards += reward;
+        state.stats.last_difficulty = best.difficulty;
+        state.broadcast_stats();
+        info!(
+            "{} Submitted difficulty {} from {}, earned {} ORE for {} wallet(s)",
+            "OK".bold().green(),
+            best.difficulty,
+            best.wallet,
+            amount_u64_to_string(reward),
+            payouts.len()
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use drillx::Solution;
+use ore_cli::pool::PoolMessage;
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+use crate::pplns::Pplns;
+
+pub type SharedState = Arc<Mutex<PoolState>>;
+
+/// A connected miner.
+pub struct Client {
+    sender: UnboundedSender<Message>,
+    /// Wallet of the last share the miner submitted.
+    wallet: Option<String>,
+    /// Slice of the nonce space assigned for the current round, if the miner joined before it
+    /// started.
+    nonce_range: Option<Range<u64>>,
+}
+
+/// Best solution submitted in a round.
+pub struct BestShare {
+    pub solution: Solution,
+    pub difficulty: u32,
+    pub wallet: String,
+}
+
+pub struct Round {
+    challenge: [u8; 32],
+    min_difficulty: u32,
+    cutoff: u64,
+    shared: HashSet<Uuid>,
+    pub best: Option<BestShare>,
+    /// Expected number of hashes behind the shares accepted this round.
+    pub hashes: f64,
+}
+
+impl Round {
+    pub fn hashrate(&self) -> f64 {
+        self.hashes / self.cutoff.max(1) as f64
+    }
+}
+
+#[derive(Default)]
+pub struct PoolStats {
+    pub rounds: u64,
+    pub shares: u64,
+    pub rewards: u64,
+    pub hashrate: f64,
+    pub last_difficulty: u32,
+}
+
+pub struct PoolState {
+    clients: HashMap<Uuid, Client>,
+    round: Option<Round>,
+    pub pplns: Pplns,
+    pub stats: PoolStats,
+}
+
+impl PoolState {
+    pub fn new(pplns_window: usize) -> Self {
+        Self {
+            clients: HashMap::new(),
+            round: None,
+            pplns: Pplns::new(pplns_window),
+            stats: PoolStats::default(),
+        }
+    }
+
+    pub fn connect(&mut self, id: Uuid, sender: UnboundedSender<Message>) {
+        self.clients.insert(
+            id,
+            Client {
+                sender,
+                wallet: None,
+                nonce_range: None,
+            },
+        );
+    }
+
+    pub fn disconnect(&mut self, id: &Uuid) {
+        self.clients.remove(id);
+    }
+
+    pub fn num_clients(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn send(&self, id: &Uuid, message: &PoolMessage) {
+        if let Some(client) = self.clients.get(id) {
+            let message = serde_json::to_string(message).unwrap();
+            client.sender.send(Message::Text(message)).ok();
+        }
+    }
+
+    /// Splits the nonce space evenly across the connected miners and sends each one the
+    /// challenge with its slice.
+    pub fn start_round(&mut self, challenge: [u8; 32], cutoff: u64, min_difficulty: u32) {
+        let ids: Vec<Uuid> = self.clients.keys().copied().collect();
+        let span = u64::MAX / ids.len().max(1) as u64;
+        for (i, id) in ids.iter().enumerate() {
+            let nonce_start = span * i as u64;
+            let nonce_end = if i.eq(&(ids.len() - 1)) {
+                u64::MAX
+            } else {
+                nonce_start + span
+            };
+            if let Some(client) = self.clients.get_mut(id) {
+                client.nonce_range = Some(nonce_start..nonce_end);
+            }
+            self.send(
+                id,
+                &PoolMessage::Challenge {
+                    challenge: bs58::encode(challenge).into_string(),
+                    cutoff,
+                    min_difficulty,
+                    nonce_start,
+                    nonce_end,
+                },
+            );
+        }
+        self.round = Some(Round {
+            challenge,
+            min_difficulty,
+            cutoff,
+            shared: HashSet::new(),
+            best: None,
+            hashes: 0.0,
+        });
+    }
+
+    /// Whether every miner given a slice this round has submitted its share.
+    pub fn all_shares_in(&self) -> bool {
+        let Some(round) = &self.round else {
+            return true;
+        };
+        self.clients
+            .iter()
+            .filter(|(_, client)| client.nonce_range.is_some())
+            .all(|(id, _)| round.shared.contains(id))
+    }
+
+    pub fn end_round(&mut self) -> Option<Round> {
+        for client in self.clients.values_mut() {
+            client.nonce_range = None;
+        }
+        let round = self.round.take()?;
+        self.stats.rounds += 1;
+        self.stats.hashrate = round.hashrate();
+        Some(round)
+    }
+
+    /// Checks a share against the current round, recording it if valid.
+    pub fn submit_share(
+        &mut self,
+        id: Uuid,
+        wallet: String,
+        nonce: u64,
+        digest: &str,
+    ) -> PoolMessage {
+        let reject = |reason: &str| PoolMessage::Rejected {
+            reason: reason.to_string(),
+        };
+        let Some(round) = self.round.as_mut() else {
+            return reject("No round in progress");
+        };
+        let Some(client) = self.clients.get_mut(&id) else {
+            return reject("Not connected");
+        };
+        let Some(range) = &client.nonce_range else {
+            return reject("No challenge was assigned to this miner this round");
+        };
+        if !range.contains(&nonce) {
+            return reject("Nonce is outside the assigned range");
+        }
+        if Pubkey::from_str(&wallet).is_err() {
+            return reject("Invalid wallet");
+        }
+        let Some(digest) = bs58::decode(digest)
+            .into_vec()
+            .ok()
+            .and_then(|digest| <[u8; 16]>::try_from(digest).ok())
+        else {
+            return reject("Invalid digest");
+        };
+
+        // Verify the solution rather than trusting the reported difficulty
+        let solution = Solution::new(digest, nonce.to_le_bytes());
+        if !solution.is_valid(&round.challenge) {
+            return reject("Invalid solution");
+        }
+        let difficulty = solution.to_hash().difficulty();
+        if difficulty.lt(&round.min_difficulty) {
+            return reject("Difficulty is below the minimum");
+        }
+        if !round.shared.insert(id) {
+            return reject("Already submitted a share this round");
+        }
+
+        // Record share
+        client.wallet = Some(wallet.clone());
+        round.hashes += 2f64.powi(difficulty as i32);
+        if round
+            .best
+            .as_ref()
+            .map_or(true, |best| difficulty.gt(&best.difficulty))
+        {
+            round.best = Some(BestShare {
+                solution,
+                difficulty,
+                wallet: wallet.clone(),
+            });
+        }
+        self.pplns.record(wallet, difficulty);
+        self.stats.shares += 1;
+        PoolMessage::Accepted { difficulty }
+    }
+
+    /// Sends each miner the pool hashrate and the ORE credited to its wallet so far.
+    pub fn broadcast_stats(&self) {
+        for (id, client) in &self.clients {
+            let earnings = client
+                .wallet
+                .as_deref()
+                .map_or(0, |wallet| self.pplns.balance(wallet));
+            self.send(
+                id,
+                &PoolMessage::Stats {
+                    hashrate: self.stats.hashrate,
+                    estimated_earnings: ore_cli::utils::amount_u64_to_f64(earnings),
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::net::SocketAddr;
+
+use futures::{SinkExt, StreamExt};
+use ore_cli::pool::PoolMessage;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::mpsc,
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::StatusCode,
+        Message,
+    },
+};
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::state::SharedState;
+
+/// Binds the port, then accepts miners in the background.
+pub async fn serve_miners(
+    port: u16,
+    api_key: Option<String>,
+    state: SharedState,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, addr)) => {
+                    tokio::spawn(handle_miner(stream, addr, api_key.clone(), state.clone()));
+                }
+                Err(err) => error!("Failed to accept miner: {}", err),
+            }
+        }
+    });
+    Ok(())
+}
+
+async fn handle_miner(
+    stream: TcpStream,
+    addr: SocketAddr,
+    api_key: Option<String>,
+    state: SharedState,
+) {
+    // Check the api key during the handshake
+    let authorize = |request: &Request, response: Response| {
+        let Some(api_key) = api_key else {
+            return Ok(response);
+        };
+        let authorized = request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| value.eq(&format!("Bearer {}", api_key)));
+        if authorized {
+            Ok(response)
+        } else {
+            let mut response = ErrorResponse::new(Some("Invalid api key".to_string()));
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(response)
+        }
+    };
+    let stream = match accept_hdr_async(stream, authorize).await {
+        Ok(stream) => stream,
+        Err(err) => {
+            debug!("Rejected connection from {}: {}", addr, err);
+            return;
+        }
+    };
+    let id = Uuid::new_v4();
+    let (mut write, mut read) = stream.split();
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    state.lock().unwrap().connect(id, sender);
+    info!("Miner connected from {}", addr);
+
+    // Forward queued messages to the miner
+    let writer = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            if write.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // Handle shares until the miner disconnects
+    while let Some(message) = read.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(err) => {
+                debug!("Connection error from {}: {}", addr, err);
+                break;
+            }
+        };
+        let reply = match serde_json::from_str::<PoolMessage>(&text) {
+            Ok(PoolMessage::Share {
+                wallet,
+                nonce,
+                digest,
+                ..
+            }) => state
+                .lock()
+                .unwrap()
+                .submit_share(id, wallet, nonce, &digest),
+            Ok(_) => continue,
+            Err(_) => PoolMessage::Rejected {
+                reason: "Unrecognized message".to_string(),
+            },
+        };
+        state.lock().unwrap().send(&id, &reply);
+    }
+    state.lock().unwrap().disconnect(&id);
+    writer.abort();
+    info!("Miner disconnected from {}", addr);
+}
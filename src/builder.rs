@@ -0,0 +1,482 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::signature::Keypair;
+use tokio::sync::RwLock;
+
+#[cfg(feature = "cli")]
+use crate::args::MineArgs;
+use crate::{
+    args::{
+        ClockDriftAction, CpuGovernor, InsufficientSolPolicy, LowDifficultyPolicy, NonceStrategy,
+        OutputFormat, PriorityFeeMode, ThreadPriorityLevel,
+    },
+    error::MinerError,
+    new_rpc_client,
+    propagation::PropagationEstimate,
+    send_and_confirm::{RetryPolicy, MIN_SOL_BALANCE},
+    session::MinerStats,
+    Miner,
+};
+
+/// Default number of times a transaction is rebroadcast, matching `--max-retries`.
+const DEFAULT_MAX_RETRIES: usize = 5;
+
+/// Default percentile of recent fees paid in dynamic fee mode, matching
+/// `--priority-fee-percentile`.
+const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 75;
+
+/// Options for a mining session, independent of how they were parsed. See `MineArgs` for
+/// what each option does.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MineConfig {
+    pub cores: u64,
+    pub buffer_time: u64,
+    pub buffer_time_adaptive: bool,
+    pub latency_window: usize,
+    pub min_difficulty: Option<u32>,
+    pub difficulty_percentile: Option<u8>,
+    pub alert_difficulty: Option<u32>,
+    pub memory_limit: Option<u64>,
+    pub max_rss_mb: Option<u64>,
+    pub max_wait_secs: u64,
+    pub max_clock_drift: Option<u64>,
+    pub max_clock_drift_action: ClockDriftAction,
+    pub on_low_difficulty: LowDifficultyPolicy,
+    pub low_difficulty_wait: Duration,
+    pub challenge_refresh_interval: Option<Duration>,
+    pub prefetch_clock: bool,
+    pub watchdog_timeout: Option<u64>,
+    pub watchdog_file: Option<String>,
+    pub pass_interval: Option<u64>,
+    pub bus_cache_ttl: u64,
+    pub proof_cache_ttl: Option<u64>,
+    pub wallets: Option<String>,
+    pub threads_per_wallet: Option<u64>,
+    pub db: Option<String>,
+    pub resume: bool,
+    pub resume_session: Option<String>,
+    pub log_file: Option<String>,
+    pub log_rotate_size: Option<u64>,
+    pub log_rotate_keep: usize,
+    pub export_md: Option<String>,
+    pub max_temp: Option<f32>,
+    pub max_sol_per_pass: Option<f64>,
+    pub auto_claim: bool,
+    pub min_claim_ore: f64,
+    pub stake_threshold_multiplier: Option<f64>,
+    pub stake_auto: bool,
+    pub min_stake_ore: f64,
+    pub stake_before_mine: bool,
+    pub min_stake_amount: f64,
+    pub auto_topup: Option<String>,
+    pub topup_amount: f64,
+    pub on_insufficient_sol: InsufficientSolPolicy,
+    pub metrics_port: Option<u16>,
+    pub grpc_port: Option<u16>,
+    pub notify_discord: Option<String>,
+    pub notify_interval: u64,
+    pub notify_telegram: Option<String>,
+    pub notify_chat_id: Option<String>,
+    pub ore_price_feed: Option<String>,
+    pub ema_alpha: f64,
+    pub shutdown_timeout: u64,
+    pub dry_run: bool,
+    pub skip_register: bool,
+    pub force_register: bool,
+    pub epoch_wait: bool,
+    pub pass_limit: Option<u64>,
+    pub max_passes_per_hour: Option<u64>,
+    pub schedule: Option<String>,
+    pub tui: bool,
+    pub quiet: bool,
+    pub no_verify: bool,
+    pub simulate: bool,
+    pub rayon: bool,
+    pub mem_prealloc: bool,
+    pub pin_threads: bool,
+    pub thread_priority: ThreadPriorityLevel,
+    pub cpu_governor: Option<CpuGovernor>,
+    pub nonce_strategy: NonceStrategy,
+    pub tpu_client: bool,
+    pub jito: bool,
+    pub jito_url: String,
+    pub jito_tip_lamports: u64,
+    pub target_hashrate: Option<u64>,
+    pub extra_instruction: Vec<String>,
+}
+
+impl Default for MineConfig {
+    /// Uses the same defaults as `ore mine` with no options.
+    fn default() -> Self {
+        Self {
+            cores: 1,
+            buffer_time: 5,
+            buffer_time_adaptive: false,
+            latency_window: 5,
+            min_difficulty: None,
+            difficulty_percentile: None,
+            alert_difficulty: None,
+            memory_limit: None,
+            max_rss_mb: None,
+            max_wait_secs: 300,
+            max_clock_drift: None,
+            max_clock_drift_action: ClockDriftAction::Warn,
+            on_low_difficulty: LowDifficultyPolicy::Submit,
+            low_difficulty_wait: Duration::from_secs(30),
+            challenge_refresh_interval: None,
+            prefetch_clock: false,
+            watchdog_timeout: None,
+            watchdog_file: None,
+            pass_interval: None,
+            bus_cache_ttl: 30,
+            proof_cache_ttl: None,
+            wallets: None,
+            threads_per_wallet: None,
+            db: None,
+            resume: false,
+            resume_session: None,
+            log_file: None,
+            log_rotate_size: None,
+            log_rotate_keep: 5,
+            export_md: None,
+            max_temp: None,
+            max_sol_per_pass: None,
+            auto_claim: false,
+            min_claim_ore: 0.1,
+            stake_threshold_multiplier: None,
+            stake_auto: false,
+            min_stake_ore: 0.5,
+            stake_before_mine: false,
+            min_stake_amount: 0.5,
+            auto_topup: None,
+            topup_amount: 0.05,
+            on_insufficient_sol: InsufficientSolPolicy::Pause,
+            metrics_port: None,
+            grpc_port: None,
+            notify_discord: None,
+            notify_interval: 10,
+            notify_telegram: None,
+            notify_chat_id: None,
+            ore_price_feed: None,
+            ema_alpha: 0.3,
+            shutdown_timeout: 120,
+            dry_run: false,
+            skip_register: false,
+            force_register: false,
+            epoch_wait: false,
+            pass_limit: None,
+            max_passes_per_hour: None,
+            schedule: None,
+            tui: false,
+            quiet: false,
+            no_verify: false,
+            simulate: false,
+            rayon: false,
+            mem_prealloc: false,
+            pin_threads: false,
+            thread_priority: ThreadPriorityLevel::Normal,
+            cpu_governor: None,
+            nonce_strategy: NonceStrategy::Linear,
+            tpu_client: false,
+            jito: false,
+            jito_url: "https://mainnet.block-engine.jito.wtf".to_string(),
+            jito_tip_lamports: 1000,
+            target_hashrate: None,
+            extra_instruction: vec![],
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl From<MineArgs> for MineConfig {
+    fn from(args: MineArgs) -> Self {
+        Self {
+            cores: args.cores,
+            buffer_time: args.buffer_time,
+            buffer_time_adaptive: args.buffer_time_adaptive,
+            latency_window: args.latency_window,
+            min_difficulty: args.min_difficulty,
+            difficulty_percentile: args.difficulty_percentile,
+            alert_difficulty: args.alert_difficulty,
+            memory_limit: args.memory_limit,
+            max_rss_mb: args.max_rss_mb,
+            max_wait_secs: args.max_wait_secs,
+            max_clock_drift: args.max_clock_drift,
+            max_clock_drift_action: args.max_clock_drift_action,
+            on_low_difficulty: args.on_low_difficulty,
+            low_difficulty_wait: args.low_difficulty_wait,
+            challenge_refresh_interval: args.challenge_refresh_interval,
+            prefetch_clock: args.prefetch_clock,
+            watchdog_timeout: args.watchdog_timeout,
+            watchdog_file: args.watchdog_file,
+            pass_interval: args.pass_interval,
+            bus_cache_ttl: args.bus_cache_ttl,
+            proof_cache_ttl: args.proof_cache_ttl,
+            wallets: args.wallets,
+            threads_per_wallet: args.threads_per_wallet,
+            db: args.db,
+            resume: args.resume,
+            resume_session: args.resume_session,
+            log_file: args.log_file,
+            log_rotate_size: args.log_rotate_size,
+            log_rotate_keep: args.log_rotate_keep,
+            export_md: args.export_md,
+            max_temp: args.max_temp,
+            max_sol_per_pass: args.max_sol_per_pass,
+            auto_claim: args.auto_claim,
+            min_claim_ore: args.min_claim_ore,
+            stake_threshold_multiplier: args.stake_threshold_multiplier,
+            stake_auto: args.stake_auto,
+            min_stake_ore: args.min_stake_ore,
+            stake_before_mine: args.stake_before_mine,
+            min_stake_amount: args.min_stake_amount,
+            auto_topup: args.auto_topup,
+            topup_amount: args.topup_amount,
+            on_insufficient_sol: args.on_insufficient_sol,
+            metrics_port: args.metrics_port,
+            grpc_port: args.grpc_port,
+            notify_discord: args.notify_discord,
+            notify_interval: args.notify_interval,
+            notify_telegram: args.notify_telegram,
+            notify_chat_id: args.notify_chat_id,
+            ore_price_feed: args.ore_price_feed,
+            ema_alpha: args.ema_alpha,
+            shutdown_timeout: args.shutdown_timeout,
+            dry_run: args.dry_run,
+            skip_register: args.skip_register,
+            force_register: args.force_register,
+            epoch_wait: args.epoch_wait,
+            pass_limit: args.pass_limit,
+            max_passes_per_hour: args.max_passes_per_hour,
+            schedule: args.schedule,
+            tui: args.tui,
+            quiet: args.quiet,
+            no_verify: args.no_verify,
+            simulate: args.simulate,
+            rayon: args.rayon,
+            mem_prealloc: args.mem_prealloc,
+            pin_threads: args.pin_threads,
+            thread_priority: args.thread_priority,
+            cpu_governor: args.cpu_governor,
+            nonce_strategy: args.nonce_strategy,
+            tpu_client: args.tpu_client,
+            jito: args.jito,
+            jito_url: args.jito_url,
+            jito_tip_lamports: args.jito_tip_lamports,
+            target_hashrate: args.target_hashrate,
+            extra_instruction: args.extra_instruction,
+        }
+    }
+}
+
+/// Builds a [`Miner`] and the [`MineConfig`] to run it with, without going through the command
+/// line.
+#[derive(Default)]
+pub struct MinerBuilder {
+    rpc_url: Option<String>,
+    rpc_client: Option<Arc<RpcClient>>,
+    keypair: Option<Keypair>,
+    keypair_filepath: Option<String>,
+    fee_payer_filepath: Option<String>,
+    ledger_derivation_path: Option<Option<String>>,
+    priority_fee: Option<u64>,
+    lamports_per_compute_unit: Option<u64>,
+    dynamic_fee_url: Option<String>,
+    dynamic_fee_strategy: Option<String>,
+    priority_fee_mode: Option<PriorityFeeMode>,
+    priority_fee_percentile: Option<u8>,
+    max_priority_fee_lamports: Option<u64>,
+    priority_fee_cap_per_pass: Option<u64>,
+    max_retries: Option<usize>,
+    retry_policy: Option<RetryPolicy>,
+    min_sol_balance: Option<f64>,
+    output: Option<OutputFormat>,
+    config: MineConfig,
+}
+
+impl MinerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rpc_url(mut self, url: impl Into<String>) -> Self {
+        self.rpc_url = Some(url.into());
+        self
+    }
+
+    /// Sends requests through the given client instead of one built from the rpc url.
+    pub fn rpc_client(mut self, rpc_client: Arc<RpcClient>) -> Self {
+        self.rpc_client = Some(rpc_client);
+        self
+    }
+
+    pub fn keypair(mut self, keypair: Keypair) -> Self {
+        self.keypair = Some(keypair);
+        self
+    }
+
+    /// Reads the keypair from a file when signing, unless a keypair or ledger is also given.
+    pub fn keypair_filepath(mut self, path: impl Into<String>) -> Self {
+        self.keypair_filepath = Some(path.into());
+        self
+    }
+
+    /// Pays transaction fees from the keypair at this path instead of the signer.
+    pub fn fee_payer_filepath(mut self, path: impl Into<String>) -> Self {
+        self.fee_payer_filepath = Some(path.into());
+        self
+    }
+
+    /// Signs with a Ledger hardware wallet, at the default derivation path if none is given.
+    pub fn ledger(mut self, derivation_path: Option<String>) -> Self {
+        self.ledger_derivation_path = Some(derivation_path);
+        self
+    }
+
+    pub fn threads(mut self, threads: u64) -> Self {
+        self.config.cores = threads;
+        self
+    }
+
+    pub fn buffer_time(mut self, secs: u64) -> Self {
+        self.config.buffer_time = secs;
+        self
+    }
+
+    pub fn priority_fee(mut self, microlamports: u64) -> Self {
+        self.priority_fee = Some(microlamports);
+        self
+    }
+
+    pub fn lamports_per_compute_unit(mut self, lamports: u64) -> Self {
+        self.lamports_per_compute_unit = Some(lamports);
+        self
+    }
+
+    pub fn dynamic_fee_url(mut self, url: impl Into<String>) -> Self {
+        self.dynamic_fee_url = Some(url.into());
+        self
+    }
+
+    pub fn dynamic_fee_strategy(mut self, strategy: impl Into<String>) -> Self {
+        self.dynamic_fee_strategy = Some(strategy.into());
+        self
+    }
+
+    pub fn priority_fee_mode(mut self, mode: PriorityFeeMode) -> Self {
+        self.priority_fee_mode = Some(mode);
+        self
+    }
+
+    pub fn priority_fee_percentile(mut self, percentile: u8) -> Self {
+        self.priority_fee_percentile = Some(percentile);
+        self
+    }
+
+    pub fn max_priority_fee_lamports(mut self, lamports: u64) -> Self {
+        self.max_priority_fee_lamports = Some(lamports);
+        self
+    }
+
+    pub fn priority_fee_cap_per_pass(mut self, lamports: u64) -> Self {
+        self.priority_fee_cap_per_pass = Some(lamports);
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    pub fn min_sol_balance(mut self, sol: f64) -> Self {
+        self.min_sol_balance = Some(sol);
+        self
+    }
+
+    pub fn output(mut self, output: OutputFormat) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Replaces every mining option at once, keeping threads and buffer time set so far.
+    pub fn mine_config(mut self, config: MineConfig) -> Self {
+        self.config = MineConfig {
+            cores: self.config.cores,
+            buffer_time: self.config.buffer_time,
+            ..config
+        };
+        self
+    }
+
+    /// Returns the mining options to pass to [`Miner::mine`].
+    pub fn config(&self) -> MineConfig {
+        self.config.clone()
+    }
+
+    pub fn build(self) -> Result<Miner, MinerError> {
+        if self.keypair.is_none()
+            && self.keypair_filepath.is_none()
+            && self.ledger_derivation_path.is_none()
+        {
+            return Err(MinerError::MissingKeypair);
+        }
+        if self.config.cores.eq(&0) {
+            return Err(MinerError::InvalidThreads);
+        }
+        let output = self.output.unwrap_or(OutputFormat::Human);
+        let rpc_client = match (self.rpc_client, self.rpc_url) {
+            (Some(rpc_client), _) => rpc_client,
+            (None, Some(rpc_url)) => {
+                Arc::new(new_rpc_client(HttpSender::new(rpc_url), None, output))
+            }
+            (None, None) => return Err(MinerError::MissingRpcUrl),
+        };
+        Ok(Miner {
+            keypair_filepath: self.keypair_filepath,
+            env_keypair: self.keypair,
+            priority_fee: self.priority_fee,
+            lamports_per_compute_unit: self.lamports_per_compute_unit,
+            dynamic_fee_url: self.dynamic_fee_url,
+            dynamic_fee_strategy: self.dynamic_fee_strategy,
+            priority_fee_mode: self.priority_fee_mode.unwrap_or(PriorityFeeMode::Fixed),
+            priority_fee_percentile: self
+                .priority_fee_percentile
+                .unwrap_or(DEFAULT_PRIORITY_FEE_PERCENTILE),
+            max_priority_fee_lamports: self.max_priority_fee_lamports,
+            priority_fee_cap_per_pass: self.priority_fee_cap_per_pass,
+            max_retries: self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            retry_policy: self.retry_policy.unwrap_or(RetryPolicy::Backoff),
+            min_sol_balance: self.min_sol_balance.unwrap_or(MIN_SOL_BALANCE),
+            rpc_client,
+            fee_payer_filepath: self.fee_payer_filepath,
+            output,
+            ledger: self.ledger_derivation_path.is_some(),
+            ledger_derivation_path: self.ledger_derivation_path.flatten(),
+            stats: Arc::new(RwLock::new(MinerStats::default())),
+            propagation: Mutex::new(PropagationEstimate::default()),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn default_config_matches_mine_defaults() {
+        let args = MineArgs::parse_from(["mine"]);
+        assert_eq!(MineConfig::default(), MineConfig::from(args));
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+
+use ore_api::{
+    consts::{BUS_ADDRESSES, BUS_COUNT},
+    state::Bus,
+};
+use ore_utils::AccountDeserialize;
+use rand::Rng;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+
+pub struct BusSelector {
+    ttl: Duration,
+    rewards: [Option<u64>; BUS_COUNT],
+    fetched_at: Option<Instant>,
+}
+
+impl BusSelector {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            rewards: [None; BUS_COUNT],
+            fetched_at: None,
+        }
+    }
+
+    /// Refetches the bus reward balances, unless the cached values are still fresh.
+    pub async fn refresh(&mut self, client: &RpcClient) {
+        if let Some(fetched_at) = self.fetched_at {
+            if fetched_at.elapsed().lt(&self.ttl) {
+                return;
+            }
+        }
+
+        // Fetch all busses in a single call
+        match client.get_multiple_accounts(&BUS_ADDRESSES).await {
+            Ok(accounts) => {
+                for (i, account) in accounts.iter().enumerate().take(BUS_COUNT) {
+                    self.rewards[i] = account
+                        .as_ref()
+                        .and_then(|account| Bus::try_from_bytes(&account.data).ok())
+                        .map(|bus| bus.rewards);
+                }
+            }
+            Err(_) => self.rewards = [None; BUS_COUNT],
+        }
+        self.fetched_at = Some(Instant::now());
+    }
+
+    /// Picks a bus at random, weighted by its remaining rewards.
+    pub fn select(&self) -> Pubkey {
+        let weights = self.weights();
+        let total = weights.iter().fold(0u64, |acc, w| acc.saturating_add(*w));
+
+        // Fall back to pure random if every fetch failed or every bus is drained
+        if total.eq(&0) {
+            let i = rand::thread_rng().gen_range(0..BUS_COUNT);
+            return BUS_ADDRESSES[i];
+        }
+
+        let mut target = rand::thread_rng().gen_range(0..total);
+        for (i, weight) in weights.iter().enumerate() {
+            if target.lt(weight) {
+                return BUS_ADDRESSES[i];
+            }
+            target -= weight;
+        }
+        BUS_ADDRESSES[BUS_COUNT - 1]
+    }
+
+    fn weights(&self) -> [u64; BUS_COUNT] {
+        // Busses that failed to fetch are weighted with the average of the known balances,
+        // so they are neither favored nor excluded.
+        let known: Vec<u64> = self.rewards.iter().flatten().copied().collect();
+        let average = if known.is_empty() {
+            0
+        } else {
+            known.iter().fold(0u64, |acc, r| acc.saturating_add(*r)) / known.len() as u64
+        };
+        let mut weights = [0; BUS_COUNT];
+        for (i, rewards) in self.rewards.iter().enumerate() {
+            weights[i] = rewards.unwrap_or(average);
+        }
+        weights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selector(rewards: [Option<u64>; BUS_COUNT]) -> BusSelector {
+        let mut selector = BusSelector::new(Duration::from_secs(30));
+        selector.rewards = rewards;
+        selector
+    }
+
+    #[test]
+    fn unknown_busses_are_weighted_with_the_average() {
+        let mut rewards = [Some(0); BUS_COUNT];
+        rewards[0] = Some(100);
+        rewards[1] = Some(300);
+        rewards[2] = None;
+        let weights = selector(rewards).weights();
+        let average = 400 / (BUS_COUNT as u64 - 1);
+        assert_eq!(weights[0], 100);
+        assert_eq!(weights[1], 300);
+        assert_eq!(weights[2], average);
+        assert_eq!(weights[3], 0);
+    }
+
+    #[test]
+    fn drained_busses_are_never_selected() {
+        let mut rewards = [Some(0); BUS_COUNT];
+        rewards[5] = Some(1_000);
+        let selector = selector(rewards);
+        for _ in 0..100 {
+            assert_eq!(selector.select(), BUS_ADDRESSES[5]);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_any_bus_without_rewards() {
+        for rewards in [[None; BUS_COUNT], [Some(0); BUS_COUNT]] {
+            let selector = selector(rewards);
+            assert_eq!(selector.weights(), [0; BUS_COUNT]);
+            assert!(BUS_ADDRESSES.contains(&selector.select()));
+        }
+    }
+}
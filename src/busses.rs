@@ -3,21 +3,23 @@ use ore_api::{
     state::Bus,
 };
 use ore_utils::AccountDeserialize;
+use tracing::info;
 
-use crate::Miner;
+use crate::{error::MineError, Miner};
 
 impl Miner {
-    pub async fn busses(&self) {
+    pub async fn busses(&self) -> Result<(), MineError> {
         let client = self.rpc_client.clone();
         for address in BUS_ADDRESSES.iter() {
-            let data = client.get_account_data(address).await.unwrap();
+            let data = client.get_account_data(address).await?;
             match Bus::try_from_bytes(&data) {
                 Ok(bus) => {
                     let rewards = (bus.rewards as f64) / 10f64.powf(TOKEN_DECIMALS as f64);
-                    println!("Bus {}: {:} ORE", bus.id, rewards);
+                    info!("Bus {}: {:} ORE", bus.id, rewards);
                 }
                 Err(_) => {}
             }
         }
+        Ok(())
     }
 }
@@ -0,0 +1,268 @@
+use colored::*;
+use ore_api::{
+    consts::{CONFIG_ADDRESS, EPOCH_DURATION},
+    state::Config,
+};
+use ore_utils::AccountDeserialize;
+use serde_json::json;
+use solana_program::{clock::Clock, native_token::lamports_to_sol, sysvar};
+use solana_sdk::signature::{read_keypair_file, Signer};
+use tracing::info;
+
+use crate::{
+    args::{CheckArgs, ThreadPriorityLevel},
+    error::MineError,
+    output::print_json_event,
+    utils::{amount_u64_to_string, proof_pubkey, try_get_proof_with_authority},
+    Miner,
+};
+
+/// Synthetic challenge hashed by the benchmark, so it does not depend on the proof.
+const BENCHMARK_CHALLENGE: [u8; 32] = [0; 32];
+
+#[derive(Clone, Copy)]
+enum CheckStatus {
+    Ok,
+    Warning,
+    Failed,
+}
+
+impl Miner {
+    pub async fn check(&self, args: CheckArgs) -> Result<(), MineError> {
+        let mut failed = false;
+
+        // Rpc connectivity
+        match self.rpc_client.get_version().await {
+            Ok(version) => self.print_check(
+                "rpc",
+                CheckStatus::Ok,
+                format!(
+                    "Connected to {} (solana-core {})",
+                    self.rpc_client.url(),
+                    version.solana_core
+                ),
+            ),
+            Err(err) => {
+                self.print_check(
+                    "rpc",
+                    CheckStatus::Failed,
+                    format!("Could not reach {}: {}", self.rpc_client.url(), err),
+                );
+                return Err(err.into());
+            }
+        }
+
+        // Keypairs
+        let signer = match self.check_signer() {
+            Ok(signer) => {
+                self.print_check(
+                    "keypair",
+                    CheckStatus::Ok,
+                    format!("Loaded {}", signer.pubkey()),
+                );
+                Some(signer)
+            }
+            Err(err) => {
+                failed = true;
+                self.print_check("keypair", CheckStatus::Failed, err);
+                None
+            }
+        };
+        let fee_payer = match &self.fee_payer_filepath {
+            Some(path) => match read_keypair_file(path) {
+                Ok(keypair) => Some(keypair.pubkey()),
+                Err(err) => {
+                    failed = true;
+                    self.print_check(
+                        "fee_payer",
+                        CheckStatus::Failed,
+                        format!("No fee payer keypair found at {}: {}", path, err),
+                    );
+                    None
+                }
+            },
+            None => signer.as_ref().map(|signer| signer.pubkey()),
+        };
+
+        // Fee payer balance
+        if let Some(fee_payer) = fee_payer {
+            match self.rpc_client.get_balance(&fee_payer).await {
+                Ok(balance) if lamports_to_sol(balance).le(&self.min_sol_balance) => self
+                    .print_check(
+                        "sol_balance",
+                        CheckStatus::Warning,
+                        format!(
+                            "{} SOL in {}. Top up with at least {} SOL before mining",
+                            lamports_to_sol(balance),
+                            fee_payer,
+                            self.min_sol_balance
+                        ),
+                    ),
+                Ok(balance) => self.print_check(
+                    "sol_balance",
+                    CheckStatus::Ok,
+                    format!("{} SOL in {}", lamports_to_sol(balance), fee_payer),
+                ),
+                Err(err) => {
+                    failed = true;
+                    self.print_check(
+                        "sol_balance",
+                        CheckStatus::Failed,
+                        format!("Could not fetch balance of {}: {}", fee_payer, err),
+                    );
+                }
+            }
+        }
+
+        // Proof and registration
+        let proof = match &signer {
+            Some(signer) => {
+                let proof = try_get_proof_with_authority(&self.rpc_client, signer.pubkey()).await;
+                match proof {
+                    Some(proof) => self.print_check(
+                        "proof",
+                        CheckStatus::Ok,
+                        format!(
+                            "Registered at {} with {} ORE staked",
+                            proof_pubkey(signer.pubkey()),
+                            amount_u64_to_string(proof.balance)
+                        ),
+                    ),
+                    None => self.print_check(
+                        "proof",
+                        CheckStatus::Warning,
+                        "Not registered. Mining registers it, or run `ore register`".to_string(),
+                    ),
+                }
+                proof
+            }
+            None => None,
+        };
+
+        // Cutoff and epoch timing
+        match self.check_clock_and_config().await {
+            Ok((clock, config)) => {
+                if let Some(proof) = proof {
+                    let cutoff = self.get_cutoff(proof, args.buffer_time, &clock);
+                    self.print_check(
+                        "cutoff",
+                        CheckStatus::Ok,
+                        format!(
+                            "{} sec to mine with a {} sec buffer",
+                            cutoff, args.buffer_time
+                        ),
+                    );
+                }
+                let epoch_resets_in = config
+                    .last_reset_at
+                    .saturating_add(EPOCH_DURATION)
+                    .saturating_sub(clock.unix_timestamp)
+                    .max(0);
+                self.print_check(
+                    "epoch",
+                    CheckStatus::Ok,
+                    format!(
+                        "Resets in {} sec, minimum difficulty {}",
+                        epoch_resets_in, config.min_difficulty
+                    ),
+                );
+            }
+            Err(err) => {
+                failed = true;
+                self.print_check("epoch", CheckStatus::Failed, err);
+            }
+        }
+
+        // Hashpower
+        self.check_num_cores(args.cores);
+        let best_hash = Self::find_hash_par(
+            BENCHMARK_CHALLENGE,
+            args.benchmark_duration,
+            args.cores,
+            0,
+            None,
+            None,
+            false,
+            ThreadPriorityLevel::Normal,
+        )
+        .await;
+        self.print_check(
+            "hashpower",
+            CheckStatus::Ok,
+            format!(
+                "{} H/sec on {} core(s)",
+                best_hash
+                    .hashes
+                    .saturating_div(args.benchmark_duration.max(1)),
+                args.cores
+            ),
+        );
+
+        if self.is_json() {
+            print_json_event("check_summary", json!({ "passed": !failed }));
+        } else if !failed {
+            info!("{} Ready to mine", "OK".bold().green());
+        }
+        if failed {
+            return Err(MineError::ChecksFailed);
+        }
+        Ok(())
+    }
+
+    /// Loads the signer, returning an error instead of panicking on a missing keypair.
+    fn check_signer(&self) -> Result<Box<dyn Signer>, String> {
+        if self.ledger || self.env_keypair.is_some() {
+            return self.try_signer().map_err(|err| err.to_string());
+        }
+        let path = self
+            .keypair_filepath
+            .as_ref()
+            .ok_or("No keypair provided".to_string())?;
+        read_keypair_file(path)
+            .map(|keypair| Box::new(keypair) as Box<dyn Signer>)
+            .map_err(|err| format!("No keypair found at {}: {}", path, err))
+    }
+
+    async fn check_clock_and_config(&self) -> Result<(Clock, Config), String> {
+        let data = self
+            .rpc_client
+            .get_account_data(&sysvar::clock::ID)
+            .await
+            .map_err(|err| format!("Could not fetch the clock: {}", err))?;
+        let clock = bincode::deserialize::<Clock>(&data)
+            .map_err(|err| format!("Could not parse the clock: {}", err))?;
+        let data = self
+            .rpc_client
+            .get_account_data(&CONFIG_ADDRESS)
+            .await
+            .map_err(|err| format!("Could not fetch the program config: {}", err))?;
+        let config = Config::try_from_bytes(&data)
+            .map_err(|err| format!("Could not parse the program config: {}", err))?;
+        Ok((clock, *config))
+    }
+
+    fn print_check(&self, name: &str, status: CheckStatus, message: String) {
+        if self.is_json() {
+            let status = match status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warning => "warning",
+                CheckStatus::Failed => "failed",
+            };
+            print_json_event(
+                "check",
+                json!({
+                    "name": name,
+                    "status": status,
+                    "message": message,
+                }),
+            );
+            return;
+        }
+        let status = match status {
+            CheckStatus::Ok => "OK".bold().green(),
+            CheckStatus::Warning => "WARNING".bold().yellow(),
+            CheckStatus::Failed => "FAILED".bold().red(),
+        };
+        info!("{} {}: {}", status, name, message);
+    }
+}
@@ -1,51 +1,80 @@
+#[cfg(feature = "cli")]
 use std::str::FromStr;
 
+#[cfg(feature = "cli")]
 use colored::*;
-use ore_api::consts::MINT_ADDRESS;
+use ore_api::{consts::MINT_ADDRESS, state::Proof};
+use solana_program::instruction::Instruction;
+#[cfg(feature = "cli")]
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signature::Signer;
+#[cfg(feature = "cli")]
 use spl_token::amount_to_ui_amount;
+#[cfg(feature = "cli")]
+use tracing::warn;
 
+#[cfg(feature = "cli")]
 use crate::{
     args::ClaimArgs,
-    cu_limits::CU_LIMIT_CLAIM,
-    send_and_confirm::ComputeBudget,
+    cu_limits::{CU_LIMIT_ATA, CU_LIMIT_CLAIM},
+    error::MineError,
     utils::{amount_f64_to_u64, ask_confirm, get_proof_with_authority},
-    Miner,
 };
+use crate::{utils::get_clock, Miner};
 
+/// Claims made within one day of the last stake are penalized.
+pub const ONE_DAY: i64 = 86_400;
+
+#[cfg(feature = "cli")]
 impl Miner {
-    pub async fn claim(&self, args: ClaimArgs) {
-        let signer = self.signer();
+    pub async fn claim(&self, args: ClaimArgs) -> Result<(), MineError> {
+        let signer = self.try_signer()?;
         let pubkey = signer.pubkey();
-        let proof = get_proof_with_authority(&self.rpc_client, pubkey).await;
+        let proof = get_proof_with_authority(&self.rpc_client, pubkey).await?;
         let mut ixs = vec![];
-        let beneficiary = match args.to {
-            Some(to) => {
+        let mut compute_budget = CU_LIMIT_CLAIM;
+        let beneficiary = match args.destination {
+            Some(destination) => {
+                // Claim to an existing token account
+                let token_account = Pubkey::from_str(&destination).map_err(|_| {
+                    MineError::InvalidArgs(format!("Invalid destination address: {}", destination))
+                })?;
+                let Ok(Some(_)) = self.rpc_client.get_token_account(&token_account).await else {
+                    return Err(MineError::InvalidArgs(format!(
+                        "Token account {} does not exist",
+                        token_account
+                    )));
+                };
+                token_account
+            }
+            None => {
                 // Create beneficiary token account, if needed
-                let wallet = Pubkey::from_str(&to).expect("Failed to parse wallet address");
+                let wallet = match args.to {
+                    Some(to) => Pubkey::from_str(&to).map_err(|_| {
+                        MineError::InvalidArgs(format!("Invalid wallet address: {}", to))
+                    })?,
+                    None => pubkey,
+                };
                 let benefiary_tokens = spl_associated_token_account::get_associated_token_address(
                     &wallet,
                     &MINT_ADDRESS,
                 );
-                if self
-                    .rpc_client
-                    .get_token_account(&benefiary_tokens)
-                    .await
-                    .is_err()
-                {
+                if !matches!(
+                    self.rpc_client.get_token_account(&benefiary_tokens).await,
+                    Ok(Some(_))
+                ) {
                     ixs.push(
                         spl_associated_token_account::instruction::create_associated_token_account(
-                            &signer.pubkey(),
+                            &pubkey,
                             &wallet,
                             &ore_api::consts::MINT_ADDRESS,
                             &spl_token::id(),
                         ),
                     );
+                    compute_budget += CU_LIMIT_ATA;
                 }
                 benefiary_tokens
             }
-            None => self.initialize_ata().await,
         };
 
         // Parse amount to claim
@@ -54,6 +83,23 @@ impl Miner {
         } else {
             proof.balance
         };
+        if amount.gt(&proof.balance) {
+            return Err(MineError::InvalidArgs(format!(
+                "Cannot claim {} ORE. Stake balance is {} ORE",
+                amount_to_ui_amount(amount, ore_api::consts::TOKEN_DECIMALS),
+                amount_to_ui_amount(proof.balance, ore_api::consts::TOKEN_DECIMALS),
+            )));
+        }
+
+        // Show the penalty for claiming too soon after staking
+        let clock = get_clock(&self.rpc_client).await?;
+        let penalty = claim_penalty(&proof, amount, clock.unix_timestamp);
+        if penalty.gt(&0) {
+            warn!(
+                "Claiming within one day of staking burns {} ORE",
+                amount_to_ui_amount(penalty, ore_api::consts::TOKEN_DECIMALS),
+            );
+        }
 
         // Confirm user wants to claim
         if !ask_confirm(
@@ -67,43 +113,76 @@ impl Miner {
             )
             .as_str(),
         ) {
-            return;
+            return Ok(());
         }
 
         // Send and confirm
         ixs.push(ore_api::instruction::claim(pubkey, beneficiary, amount));
-        self.send_and_confirm(&ixs, ComputeBudget::Fixed(CU_LIMIT_CLAIM), false)
-            .await
-            .ok();
+        self.send_and_confirm(
+            &ixs,
+            self.compute_budget(compute_budget),
+            false,
+            self.retry_policy,
+        )
+        .await?;
+        Ok(())
     }
+}
 
-    async fn initialize_ata(&self) -> Pubkey {
-        // Initialize client.
-        let signer = self.signer();
-        let client = self.rpc_client.clone();
-
-        // Build instructions.
-        let token_account_pubkey = spl_associated_token_account::get_associated_token_address(
-            &signer.pubkey(),
-            &ore_api::consts::MINT_ADDRESS,
-        );
+impl Miner {
+    /// Returns the instructions to claim the full stake to the signer's token account, if the
+    /// penalty window has elapsed and the stake exceeds the minimum amount. With a threshold
+    /// multiplier, claims that fraction of the penalty-free stake instead, even within the
+    /// window. Also returns the amount claimed and the amount claimable without a penalty.
+    pub async fn auto_claim_ixs(
+        &self,
+        signer: &dyn Signer,
+        proof: &Proof,
+        min_amount: u64,
+        threshold_multiplier: Option<f64>,
+    ) -> Option<(Vec<Instruction>, u64, u64)> {
+        if proof.balance.eq(&0) || proof.balance.lt(&min_amount) {
+            return None;
+        }
+        let clock = get_clock(&self.rpc_client).await.ok()?;
+        let penalty_free =
+            proof
+                .balance
+                .saturating_sub(claim_penalty(proof, proof.balance, clock.unix_timestamp));
 
-        // Check if ata already exists
-        if let Ok(Some(_ata)) = client.get_token_account(&token_account_pubkey).await {
-            return token_account_pubkey;
+        // Without a multiplier, wait out the penalty and claim everything
+        let amount = match threshold_multiplier {
+            Some(multiplier) => (penalty_free as f64 * multiplier) as u64,
+            None if penalty_free.lt(&proof.balance) => return None,
+            None => proof.balance,
+        };
+        if amount.eq(&0) || amount.lt(&min_amount) {
+            return None;
         }
-        // Sign and send transaction.
-        let ix = spl_associated_token_account::instruction::create_associated_token_account(
+        let beneficiary = spl_associated_token_account::get_associated_token_address(
             &signer.pubkey(),
-            &signer.pubkey(),
-            &ore_api::consts::MINT_ADDRESS,
-            &spl_token::id(),
+            &MINT_ADDRESS,
         );
-        self.send_and_confirm(&[ix], ComputeBudget::Dynamic, false)
-            .await
-            .ok();
+        let ixs = vec![
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &signer.pubkey(),
+                &signer.pubkey(),
+                &MINT_ADDRESS,
+                &spl_token::id(),
+            ),
+            ore_api::instruction::claim(signer.pubkey(), beneficiary, amount),
+        ];
+        Some((ixs, amount, penalty_free))
+    }
+}
 
-        // Return token account address
-        token_account_pubkey
+/// Returns the amount burned when claiming within one day of the last stake. The penalty
+/// decreases linearly over the day.
+pub fn claim_penalty(proof: &Proof, amount: u64, now: i64) -> u64 {
+    let penalty_ends_at = proof.last_stake_at.saturating_add(ONE_DAY);
+    if now.ge(&penalty_ends_at) {
+        return 0;
     }
+    let remaining = penalty_ends_at.saturating_sub(now).min(ONE_DAY) as u128;
+    (amount as u128 * remaining / ONE_DAY as u128) as u64
 }
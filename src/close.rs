@@ -4,16 +4,17 @@ use spl_token::amount_to_ui_amount;
 
 use crate::{
     args::ClaimArgs,
-    send_and_confirm::ComputeBudget,
+    cu_limits::CU_LIMIT_MAX,
+    error::MineError,
     utils::{ask_confirm, get_proof_with_authority},
     Miner,
 };
 
 impl Miner {
-    pub async fn close(&self) {
+    pub async fn close(&self) -> Result<(), MineError> {
         // Confirm proof exists
-        let signer = self.signer();
-        let proof = get_proof_with_authority(&self.rpc_client, signer.pubkey()).await;
+        let signer = self.try_signer()?;
+        let proof = get_proof_with_authority(&self.rpc_client, signer.pubkey()).await?;
 
         // Confirm the user wants to close.
         if !ask_confirm(
@@ -23,7 +24,7 @@ impl Miner {
                 if proof.balance.gt(&0) { "claim your stake and "} else { "" }
             ).as_str()
         ) {
-            return;
+            return Ok(());
         }
 
         // Claim stake
@@ -31,14 +32,20 @@ impl Miner {
             self.claim(ClaimArgs {
                 amount: None,
                 to: None,
+                destination: None,
             })
-            .await;
+            .await?;
         }
 
         // Submit close transaction
         let ix = ore_api::instruction::close(signer.pubkey());
-        self.send_and_confirm(&[ix], ComputeBudget::Dynamic, false)
-            .await
-            .ok();
+        self.send_and_confirm(
+            &[ix],
+            self.compute_budget(CU_LIMIT_MAX),
+            false,
+            self.retry_policy,
+        )
+        .await?;
+        Ok(())
     }
 }
@@ -1,20 +1,23 @@
 use colored::Colorize;
+use tracing::info;
 
 use crate::{
+    error::MineError,
     utils::{amount_u64_to_string, get_config},
     Miner,
 };
 
 impl Miner {
-    pub async fn config(&self) {
-        let config = get_config(&self.rpc_client).await;
-        println!("{}: {}", "Last reset at".bold(), config.last_reset_at);
-        println!("{}: {}", "Min difficulty".bold(), config.min_difficulty);
-        println!("{}: {}", "Base reward rate".bold(), config.base_reward_rate);
-        println!(
+    pub async fn config(&self) -> Result<(), MineError> {
+        let config = get_config(&self.rpc_client).await?;
+        info!("{}: {}", "Last reset at".bold(), config.last_reset_at);
+        info!("{}: {}", "Min difficulty".bold(), config.min_difficulty);
+        info!("{}: {}", "Base reward rate".bold(), config.base_reward_rate);
+        info!(
             "{}: {} ORE",
             "Top stake".bold(),
             amount_u64_to_string(config.top_balance)
         );
+        Ok(())
     }
 }
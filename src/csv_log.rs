@@ -0,0 +1,185 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+pub const HEADER: &str = "timestamp,pass,difficulty,hash_b58,nonce,ore_mined,sol_cost,session_ore,session_sol,sol_balance,staked_balance,cutoff_time,elapsed_seconds,threads,cpu_temp";
+
+/// Columns after every row, so logs from concurrent miners can be split apart.
+const MINER_COLUMNS: &str = "session_id,wallet_pubkey";
+
+/// Format of the timestamp appended to rotated log files.
+const ROTATED_SUFFIX_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+pub struct CsvLog {
+    path: String,
+    writer: BufWriter<std::fs::File>,
+    rotation: Option<LogRotation>,
+}
+
+/// When to rotate the log and how many rotated files to keep.
+pub struct LogRotation {
+    pub max_bytes: u64,
+    pub keep: usize,
+}
+
+#[derive(Clone)]
+pub struct CsvRow {
+    pub pass: u64,
+    pub difficulty: u32,
+    pub hash_b58: String,
+    pub nonce: u64,
+    pub ore_mined: f64,
+    pub sol_cost: f64,
+    pub session_ore: f64,
+    pub session_sol: f64,
+    pub sol_balance: f64,
+    pub staked_balance: f64,
+    pub cutoff_time: u64,
+    pub elapsed_seconds: f64,
+    pub threads: u64,
+    pub cpu_temp: Option<f32>,
+}
+
+impl CsvRow {
+    /// Formats every column after the timestamp.
+    pub fn fields(&self) -> Vec<String> {
+        vec![
+            self.pass.to_string(),
+            self.difficulty.to_string(),
+            self.hash_b58.clone(),
+            self.nonce.to_string(),
+            self.ore_mined.to_string(),
+            self.sol_cost.to_string(),
+            self.session_ore.to_string(),
+            self.session_sol.to_string(),
+            self.sol_balance.to_string(),
+            self.staked_balance.to_string(),
+            self.cutoff_time.to_string(),
+            format!("{:.3}", self.elapsed_seconds),
+            self.threads.to_string(),
+            self.cpu_temp
+                .map(|temp| temp.to_string())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+impl CsvLog {
+    /// Opens the log for appending, writing a header row if the file is new.
+    pub fn open(path: &str, rotation: Option<LogRotation>) -> std::io::Result<Self> {
+        Ok(Self {
+            path: path.to_string(),
+            writer: open_writer(path)?,
+            rotation,
+        })
+    }
+
+    pub fn append(&mut self, row: &CsvRow, session_id: &str, wallet: &str) -> std::io::Result<()> {
+        writeln!(
+            self.writer,
+            "{},{},{},{}",
+            chrono::Utc::now().to_rfc3339(),
+            row.fields().join(","),
+            session_id,
+            wallet
+        )?;
+        self.writer.flush()?;
+        self.rotate_if_full()
+    }
+
+    /// Records a pass that was abandoned by the watchdog. Only the pass, elapsed time and
+    /// thread count are filled in.
+    pub fn append_watchdog_timeout(
+        &mut self,
+        pass: u64,
+        elapsed_seconds: f64,
+        threads: u64,
+        session_id: &str,
+        wallet: &str,
+    ) -> std::io::Result<()> {
+        let mut fields = vec![String::new(); HEADER.split(',').count()];
+        fields[0] = chrono::Utc::now().to_rfc3339();
+        fields[1] = pass.to_string();
+        fields[3] = "watchdog_timeout".to_string();
+        fields[12] = format!("{:.3}", elapsed_seconds);
+        fields[13] = threads.to_string();
+        fields.push(session_id.to_string());
+        fields.push(wallet.to_string());
+        writeln!(self.writer, "{}", fields.join(","))?;
+        self.writer.flush()?;
+        self.rotate_if_full()
+    }
+
+    /// Moves the log aside once it passes the size limit, switching to a fresh file at the
+    /// original path before letting go of the old one, then deletes the oldest rotated files.
+    fn rotate_if_full(&mut self) -> std::io::Result<()> {
+        let Some(rotation) = &self.rotation else {
+            return Ok(());
+        };
+        if self
+            .writer
+            .get_ref()
+            .metadata()?
+            .len()
+            .lt(&rotation.max_bytes)
+        {
+            return Ok(());
+        }
+        let rotated = format!(
+            "{}.{}",
+            self.path,
+            chrono::Local::now().format(ROTATED_SUFFIX_FORMAT)
+        );
+        fs::rename(&self.path, rotated)?;
+        self.writer = open_writer(&self.path)?;
+        prune_rotated(&self.path, rotation.keep)
+    }
+}
+
+/// Opens a log file for appending, writing a header row if the file is new.
+fn open_writer(path: &str) -> std::io::Result<BufWriter<std::fs::File>> {
+    let exists = Path::new(path).exists();
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut writer = BufWriter::new(file);
+    if !exists {
+        writeln!(writer, "{},{}", HEADER, MINER_COLUMNS)?;
+        writer.flush()?;
+    }
+    Ok(writer)
+}
+
+/// Deletes all but the newest `keep` rotated copies of the log at `path`.
+fn prune_rotated(path: &str, keep: usize) -> std::io::Result<()> {
+    let path = Path::new(path);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!(
+        "{}.",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let mut rotated: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix(&prefix)
+                .map_or(false, |suffix| {
+                    chrono::NaiveDateTime::parse_from_str(suffix, ROTATED_SUFFIX_FORMAT).is_ok()
+                })
+        })
+        .map(|entry| entry.path())
+        .collect();
+
+    // Timestamps sort oldest first
+    rotated.sort();
+    let excess = rotated.len().saturating_sub(keep);
+    for old in &rotated[..excess] {
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
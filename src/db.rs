@@ -0,0 +1,127 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::session::WalletStats;
+
+pub struct Database {
+    conn: Connection,
+}
+
+pub struct DailySummary {
+    pub date: String,
+    pub passes: u64,
+    pub ore_mined: u64,
+    pub sol_spent: u64,
+    pub avg_difficulty: f64,
+    pub best_difficulty: u32,
+}
+
+pub struct PassRecord {
+    pub session_id: String,
+    pub wallet_pubkey: String,
+    pub pass_number: u64,
+    pub ore_mined: u64,
+    pub sol_cost: u64,
+    pub difficulty: u32,
+    pub nonce: u64,
+    pub hash_hex: String,
+}
+
+impl Database {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS passes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                wallet_pubkey TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                pass_number INTEGER NOT NULL,
+                ore_mined INTEGER NOT NULL,
+                sol_cost INTEGER NOT NULL,
+                difficulty INTEGER NOT NULL,
+                nonce TEXT NOT NULL,
+                hash_hex TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS passes_wallet_session
+                ON passes (wallet_pubkey, session_id);",
+        )?;
+        Ok(Self { conn })
+    }
+
+    pub fn insert_pass(&self, record: &PassRecord) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO passes (
+                session_id, wallet_pubkey, timestamp, pass_number,
+                ore_mined, sol_cost, difficulty, nonce, hash_hex
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                record.session_id,
+                record.wallet_pubkey,
+                chrono::Utc::now().to_rfc3339(),
+                record.pass_number as i64,
+                record.ore_mined as i64,
+                record.sol_cost as i64,
+                record.difficulty,
+                record.nonce.to_string(),
+                record.hash_hex,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the id of the most recent session recorded for the wallet.
+    pub fn latest_session_id(&self, wallet_pubkey: &str) -> rusqlite::Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT session_id FROM passes WHERE wallet_pubkey = ?1 ORDER BY id DESC LIMIT 1",
+                params![wallet_pubkey],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// Returns the totals of each UTC calendar day over the last number of days, oldest first.
+    pub fn daily_summaries(&self, days: u64) -> rusqlite::Result<Vec<DailySummary>> {
+        let since =
+            chrono::Utc::now().date_naive() - chrono::Duration::days(days.saturating_sub(1) as i64);
+        let mut stmt = self.conn.prepare(
+            "SELECT substr(timestamp, 1, 10) AS date, COUNT(*), SUM(ore_mined), SUM(sol_cost),
+                    AVG(difficulty), MAX(difficulty)
+                FROM passes WHERE date >= ?1 GROUP BY date ORDER BY date",
+        )?;
+        let rows = stmt.query_map(params![since.to_string()], |row| {
+            Ok(DailySummary {
+                date: row.get(0)?,
+                passes: row.get::<_, i64>(1)? as u64,
+                ore_mined: row.get::<_, i64>(2)? as u64,
+                sol_spent: row.get::<_, i64>(3)? as u64,
+                avg_difficulty: row.get(4)?,
+                best_difficulty: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Returns the accumulated totals of a wallet within a session.
+    pub fn session_totals(
+        &self,
+        session_id: &str,
+        wallet_pubkey: &str,
+    ) -> rusqlite::Result<WalletStats> {
+        self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(ore_mined), 0), COALESCE(SUM(sol_cost), 0)
+                FROM passes WHERE session_id = ?1 AND wallet_pubkey = ?2",
+            params![session_id, wallet_pubkey],
+            |row| {
+                Ok(WalletStats {
+                    passes: row.get::<_, i64>(0)? as u64,
+                    ore_mined: row.get::<_, i64>(1)? as u64,
+                    sol_used: row.get::<_, i64>(2)? as u64,
+                    ore_staked: 0,
+                    low_difficulty_skips: 0,
+                    est_ore_per_hour: 0.0,
+                })
+            },
+        )
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+const HISTOGRAM_BUCKET_SIZE: u32 = 5;
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// Counts passes by difficulty, in buckets of five above the minimum difficulty.
+#[derive(Debug, Default)]
+pub struct DifficultyHistogram {
+    buckets: HashMap<u32, u64>,
+}
+
+impl DifficultyHistogram {
+    pub fn record(&mut self, difficulty: u32, min_difficulty: u32) {
+        let offset = difficulty.saturating_sub(min_difficulty);
+        let bucket = min_difficulty + offset / HISTOGRAM_BUCKET_SIZE * HISTOGRAM_BUCKET_SIZE;
+        *self.buckets.entry(bucket).or_default() += 1;
+    }
+
+    /// Returns the lower bound and pass count of each bucket, in order.
+    pub fn buckets(&self) -> Vec<(u32, u64)> {
+        let mut buckets: Vec<(u32, u64)> = self
+            .buckets
+            .iter()
+            .map(|(bucket, count)| (*bucket, *count))
+            .collect();
+        buckets.sort();
+        buckets
+    }
+
+    /// Renders a line per bucket with a bar scaled to the most common bucket.
+    pub fn lines(&self) -> Vec<String> {
+        let buckets = self.buckets();
+        let total: u64 = buckets.iter().map(|(_, count)| count).sum();
+        let max = buckets.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        buckets
+            .into_iter()
+            .map(|(bucket, count)| {
+                let width = (count as f64 / max.max(1) as f64 * HISTOGRAM_WIDTH as f64).ceil();
+                format!(
+                    "{:>3}-{:<3} {:<bar_width$} {} ({:.1}%)",
+                    bucket,
+                    bucket + HISTOGRAM_BUCKET_SIZE - 1,
+                    "█".repeat(width as usize),
+                    count,
+                    count as f64 / total.max(1) as f64 * 100.0,
+                    bar_width = HISTOGRAM_WIDTH,
+                )
+            })
+            .collect()
+    }
+}
@@ -1,17 +1,155 @@
-use crate::Miner;
+use crate::{
+    args::PriorityFeeMode,
+    error::{custom_client_error, MineError},
+    send_and_confirm::ComputeBudget,
+    Miner,
+};
 
 use ore_api::consts::BUS_ADDRESSES;
 use reqwest::Client;
 use serde_json::{json, Value};
+use solana_program::pubkey::Pubkey;
+
+const LAMPORTS_PER_SIGNATURE: u64 = 5000;
 
 impl Miner {
-    pub async fn dynamic_fee(&self) -> u64 {
+    /// Returns the compute budget of a transaction requesting this many compute units, priced
+    /// from recent prioritization fees in dynamic fee mode.
+    pub fn compute_budget(&self, cu_limit: u32) -> ComputeBudget {
+        match self.priority_fee_mode {
+            PriorityFeeMode::Fixed => ComputeBudget::Fixed(cu_limit),
+            PriorityFeeMode::Dynamic => ComputeBudget::Dynamic {
+                cu_limit,
+                percentile: self.priority_fee_percentile,
+                max_lamports: self.max_priority_fee_lamports,
+            },
+        }
+    }
+
+    /// Returns the compute unit price to pay if it should be estimated, from the dynamic fee
+    /// api or the network, or None if the fixed priority fee should be used.
+    pub async fn dynamic_compute_unit_price(
+        &self,
+        compute_budget: &ComputeBudget,
+    ) -> Result<Option<u64>, MineError> {
+        if self.dynamic_fee_strategy.is_some() {
+            return self.dynamic_fee().await.map(Some);
+        }
+        Ok(match *compute_budget {
+            ComputeBudget::Fixed(_) => None,
+            ComputeBudget::Dynamic {
+                cu_limit,
+                percentile,
+                max_lamports,
+            } => Some(
+                self.recent_prioritization_fee(cu_limit, percentile, max_lamports)
+                    .await,
+            ),
+        })
+    }
+
+    /// Like dynamic_compute_unit_price, but lowers the price so the transaction's total priority
+    /// fee stays within --priority-fee-cap-per-pass, warning when it does. The cap applies to
+    /// every estimate, after the --max-priority-fee-lamports cap of the percentile estimate.
+    pub async fn capped_compute_unit_price(
+        &self,
+        compute_budget: &ComputeBudget,
+    ) -> Result<Option<u64>, MineError> {
+        let Some(fee) = self.dynamic_compute_unit_price(compute_budget).await? else {
+            return Ok(None);
+        };
+        let cu_limit = compute_budget.cu_limit();
+        let capped = self.cap_compute_unit_price(fee, cu_limit);
+        if capped.lt(&fee) {
+            self.print_warning(&format!(
+                "[PRIORITY FEE CAPPED] dynamic={} capped={}",
+                total_priority_fee(fee, cu_limit),
+                total_priority_fee(capped, cu_limit)
+            ));
+        }
+        Ok(Some(capped))
+    }
+
+    fn cap_compute_unit_price(&self, fee: u64, cu_limit: u32) -> u64 {
+        match self.priority_fee_cap_per_pass {
+            Some(cap) => fee.min(cap.saturating_mul(1_000_000) / (cu_limit.max(1) as u64)),
+            None => fee,
+        }
+    }
+
+    /// Returns the compute unit price to pay when no dynamic fee is estimated.
+    pub fn static_compute_unit_price(&self) -> u64 {
+        self.lamports_per_compute_unit
+            .or(self.priority_fee)
+            .unwrap_or(0)
+    }
+
+    /// Estimates the fee in lamports of a transaction with the given compute budget and number
+    /// of signatures, at the compute unit price it would currently be sent with.
+    pub async fn estimate_fee(
+        &self,
+        compute_budget: &ComputeBudget,
+        num_signatures: u64,
+    ) -> Result<u64, MineError> {
+        let cu_limit = compute_budget.cu_limit();
+        let cu_price = match self.dynamic_compute_unit_price(compute_budget).await? {
+            Some(fee) => self.cap_compute_unit_price(fee, cu_limit),
+            None => self.static_compute_unit_price(),
+        };
+        Ok(LAMPORTS_PER_SIGNATURE
+            .saturating_mul(num_signatures)
+            .saturating_add(total_priority_fee(cu_price, cu_limit)))
+    }
+
+    /// Returns the percentile of recent prioritization fees paid for the ORE accounts, lowered
+    /// so the total priority fee stays within max_lamports.
+    async fn recent_prioritization_fee(
+        &self,
+        cu_limit: u32,
+        percentile: u8,
+        max_lamports: Option<u64>,
+    ) -> u64 {
+        let ore_addresses: Vec<Pubkey> = std::iter::once(ore_api::ID)
+            .chain(BUS_ADDRESSES.iter().copied())
+            .collect();
+
+        // Fetch recent fees
+        let mut fees: Vec<u64> = match self
+            .rpc_client
+            .get_recent_prioritization_fees(&ore_addresses)
+            .await
+        {
+            Ok(fees) => fees.iter().map(|fee| fee.prioritization_fee).collect(),
+            Err(_) => return self.priority_fee.unwrap_or(0),
+        };
+        if fees.is_empty() {
+            return self.priority_fee.unwrap_or(0);
+        }
+
+        // Pick percentile
+        fees.sort_unstable();
+        let index = (fees.len() - 1) * percentile as usize / 100;
+        let fee = fees[index];
+
+        // Cap the total priority fee paid by the transaction
+        match max_lamports {
+            Some(max_lamports) => {
+                let max_fee = max_lamports.saturating_mul(1_000_000) / (cu_limit.max(1) as u64);
+                fee.min(max_fee)
+            }
+            None => fee,
+        }
+    }
+
+    /// Fetches a priority fee estimate from the dynamic fee api, failing on a request or
+    /// response that cannot be parsed.
+    pub async fn dynamic_fee(&self) -> Result<u64, MineError> {
         let ore_addresses: Vec<String> = std::iter::once(ore_api::ID.to_string())
             .chain(BUS_ADDRESSES.iter().map(|pubkey| pubkey.to_string()))
             .collect();
 
         match &self.dynamic_fee_strategy {
-            None => self.priority_fee.unwrap_or(0),
+            None => Ok(self.priority_fee.unwrap_or(0)),
             Some(strategy) => {
                 let client = Client::new();
                 let body = match strategy.as_str() {
@@ -41,7 +179,7 @@ impl Miner {
                             ]
                         })
                     }
-                    _ => return self.priority_fee.unwrap_or(0),
+                    _ => return Ok(self.priority_fee.unwrap_or(0)),
                 };
 
                 // Send request
@@ -54,10 +192,10 @@ impl Miner {
                     .json(&body)
                     .send()
                     .await
-                    .unwrap()
+                    .map_err(|err| fee_error(format!("Failed to fetch priority fee: {}", err)))?
                     .json()
                     .await
-                    .unwrap();
+                    .map_err(|err| fee_error(format!("Failed to parse priority fee: {}", err)))?;
 
                 // Parse fee
                 let calculated_fee = match strategy.as_str() {
@@ -65,27 +203,40 @@ impl Miner {
                         .as_f64()
                         .map(|fee| fee as u64)
                         .ok_or_else(|| {
-                            format!("Failed to parse priority fee. Response: {:?}", response)
-                        })
-                        .unwrap(),
+                            fee_error(format!(
+                                "Failed to parse priority fee. Response: {:?}",
+                                response
+                            ))
+                        })?,
                     "triton" => response["result"]
                         .as_array()
                         .and_then(|arr| arr.last())
                         .and_then(|last| last["prioritizationFee"].as_u64())
                         .ok_or_else(|| {
-                            format!("Failed to parse priority fee. Response: {:?}", response)
-                        })
-                        .unwrap(),
-                    _ => return self.priority_fee.unwrap_or(0),
+                            fee_error(format!(
+                                "Failed to parse priority fee. Response: {:?}",
+                                response
+                            ))
+                        })?,
+                    _ => return Ok(self.priority_fee.unwrap_or(0)),
                 };
 
                 // Check if the calculated fee is higher than max
                 if let Some(max_fee) = self.priority_fee {
-                    calculated_fee.min(max_fee)
+                    Ok(calculated_fee.min(max_fee))
                 } else {
-                    calculated_fee
+                    Ok(calculated_fee)
                 }
             }
         }
     }
 }
+
+fn fee_error(message: String) -> MineError {
+    MineError::RpcError(custom_client_error(message))
+}
+
+/// Returns the priority fee in lamports of a transaction at the compute unit price.
+fn total_priority_fee(cu_price: u64, cu_limit: u32) -> u64 {
+    (cu_price as u128 * cu_limit as u128).div_ceil(1_000_000) as u64
+}
@@ -0,0 +1,85 @@
+use std::time::Instant;
+
+/// An exponential moving average of ORE mined per hour, weighting recent passes more heavily.
+pub struct EarningsRate {
+    alpha: f64,
+    ore_per_hour: Option<f64>,
+    last_recorded_at: Option<Instant>,
+}
+
+impl EarningsRate {
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            ore_per_hour: None,
+            last_recorded_at: None,
+        }
+    }
+
+    /// Records the ORE mined by a pass and returns the updated estimate. Time is measured from
+    /// the previous pass, or from the start of the first one, so rests count against the rate.
+    pub fn record(&mut self, ore: f64, pass_started_at: Instant) -> f64 {
+        self.record_at(ore, pass_started_at, Instant::now())
+    }
+
+    fn record_at(&mut self, ore: f64, pass_started_at: Instant, now: Instant) -> f64 {
+        let since = self.last_recorded_at.unwrap_or(pass_started_at);
+        self.last_recorded_at = Some(now);
+        let hours = now.duration_since(since).as_secs_f64() / 3600.0;
+        if hours.le(&0.0) {
+            return self.ore_per_hour.unwrap_or_default();
+        }
+        let rate = ore / hours;
+        let ore_per_hour = match self.ore_per_hour {
+            Some(previous) => self.alpha * rate + (1.0 - self.alpha) * previous,
+            None => rate,
+        };
+        self.ore_per_hour = Some(ore_per_hour);
+        ore_per_hour
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn first_pass_sets_the_rate() {
+        let start = Instant::now();
+        let mut rate = EarningsRate::new(0.3);
+        assert_eq!(rate.record_at(2.0, start, start + HOUR / 2), 4.0);
+    }
+
+    #[test]
+    fn later_passes_are_weighted_by_alpha() {
+        let start = Instant::now();
+        let mut rate = EarningsRate::new(0.25);
+        rate.record_at(1.0, start, start + HOUR);
+        // 0.25 * 5.0 + 0.75 * 1.0
+        let ore_per_hour = rate.record_at(5.0, start, start + HOUR * 2);
+        assert!((ore_per_hour - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rests_count_against_the_rate() {
+        let start = Instant::now();
+        let mut rate = EarningsRate::new(1.0);
+        rate.record_at(1.0, start, start + HOUR);
+        // The next pass starts an hour after the last one was recorded.
+        let ore_per_hour = rate.record_at(1.0, start + HOUR * 2, start + HOUR * 3);
+        assert!((ore_per_hour - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_elapsed_time_keeps_the_estimate() {
+        let start = Instant::now();
+        let mut rate = EarningsRate::new(0.3);
+        assert_eq!(rate.record_at(1.0, start, start), 0.0);
+        rate.record_at(1.0, start, start + HOUR);
+        assert_eq!(rate.record_at(5.0, start, start + HOUR), 1.0);
+    }
+}
@@ -0,0 +1,183 @@
+use std::{fmt, io};
+
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::{
+    native_token::lamports_to_sol, pubkey::Pubkey, signature::Signature,
+    transaction::TransactionError,
+};
+
+/// Errors from mining and the other commands. Submission failures are classified by how a
+/// pass can recover from them.
+#[derive(Debug)]
+pub enum MineError {
+    /// The rpc failed to serve a request.
+    RpcError(ClientError),
+    /// The fee payer's balance, in SOL, is below the minimum to keep mining.
+    InsufficientBalance(f64),
+    /// The proof account does not exist or could not be parsed.
+    ProofNotFound(Pubkey),
+    /// A keypair file could not be read.
+    KeypairLoad(io::Error),
+    /// The Ledger signer could not be reached.
+    Ledger(String),
+    /// The command line options are invalid.
+    InvalidArgs(String),
+    /// The fee payer cannot cover the transaction.
+    InsufficientFunds,
+    /// The blockhash the transaction was signed with expired before it landed.
+    BlockhashExpired,
+    /// The transaction failed preflight or simulation.
+    SimulationFailed(String),
+    /// The pass was skipped because its estimated fee, in lamports, exceeds the max per pass.
+    FeeCapExceeded { fee: u64, max: u64 },
+    /// The transaction never confirmed, even after being rebroadcast.
+    TransactionDropped {
+        signature: Signature,
+        retries: usize,
+    },
+    /// The local clock drifted from the cluster clock, in seconds, by more than the max.
+    ClockDrift { drift: i64, max: u64 },
+    /// At least one of the `check` command's checks failed.
+    ChecksFailed,
+    /// The connection to the pool server failed.
+    Pool(String),
+    /// Another miner reset the epoch before the bundled reset instruction landed.
+    EpochAlreadyReset,
+    /// Any other error, such as one the cluster returned.
+    Unknown(String),
+}
+
+impl MineError {
+    /// Whether resubmitting the same transaction cannot succeed.
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            MineError::InsufficientFunds
+                | MineError::BlockhashExpired
+                | MineError::SimulationFailed(_)
+        )
+    }
+}
+
+impl fmt::Display for MineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MineError::RpcError(err) => write!(f, "{}", err),
+            MineError::InsufficientBalance(balance) => write!(
+                f,
+                "Insufficient balance: {} SOL. Please top up and restart the miner.",
+                balance
+            ),
+            MineError::ProofNotFound(address) => write!(
+                f,
+                "No proof account found at {}. Register with `ore register` first.",
+                address
+            ),
+            MineError::KeypairLoad(err) => write!(f, "Failed to load keypair: {}", err),
+            MineError::Ledger(err) => write!(f, "{}", err),
+            MineError::InvalidArgs(err) => write!(f, "{}", err),
+            MineError::InsufficientFunds => write!(f, "Insufficient funds for fee"),
+            MineError::BlockhashExpired => write!(f, "Blockhash expired"),
+            MineError::SimulationFailed(err) => write!(f, "Simulation failed: {}", err),
+            MineError::FeeCapExceeded { fee, max } => write!(
+                f,
+                "Estimated fee of {} SOL exceeds --max-sol-per-pass {} SOL",
+                lamports_to_sol(*fee),
+                lamports_to_sol(*max)
+            ),
+            MineError::TransactionDropped { signature, retries } => write!(
+                f,
+                "Transaction {} dropped after {} retries",
+                signature, retries
+            ),
+            MineError::ClockDrift { drift, max } => write!(
+                f,
+                "Local clock is {} sec {} the cluster clock, more than the max drift of {} sec. Check the system time.",
+                drift.unsigned_abs(),
+                if drift.gt(&0) { "ahead of" } else { "behind" },
+                max
+            ),
+            MineError::ChecksFailed => write!(f, "Some checks failed"),
+            MineError::Pool(err) => write!(f, "{}", err),
+            MineError::EpochAlreadyReset => write!(f, "Epoch was already reset"),
+            MineError::Unknown(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for MineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MineError::RpcError(err) => Some(err),
+            MineError::KeypairLoad(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TransactionError> for MineError {
+    fn from(err: TransactionError) -> Self {
+        match err {
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. } => MineError::InsufficientFunds,
+            TransactionError::BlockhashNotFound => MineError::BlockhashExpired,
+            err => MineError::Unknown(err.to_string()),
+        }
+    }
+}
+
+impl From<ClientError> for MineError {
+    fn from(err: ClientError) -> Self {
+        // Preflight failures carry the simulated transaction error
+        if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            message,
+            ..
+        }) = err.kind()
+        {
+            return match result.err.clone().map(MineError::from) {
+                Some(MineError::Unknown(_)) | None => MineError::SimulationFailed(message.clone()),
+                Some(err) => err,
+            };
+        }
+        match err.get_transaction_error() {
+            Some(err) => err.into(),
+            None => MineError::RpcError(err),
+        }
+    }
+}
+
+/// Wraps a failed request to a service other than the rpc, such as a fee or bundle api, so it
+/// is retried like an rpc error.
+pub(crate) fn custom_client_error(message: String) -> ClientError {
+    ClientError {
+        request: None,
+        kind: ClientErrorKind::Custom(message),
+    }
+}
+
+/// Errors from building a [`crate::Miner`] without the command line.
+#[derive(Debug)]
+pub enum MinerError {
+    /// No rpc url was given.
+    MissingRpcUrl,
+    /// No keypair was given to sign transactions with.
+    MissingKeypair,
+    /// The thread count was zero.
+    InvalidThreads,
+}
+
+impl fmt::Display for MinerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinerError::MissingRpcUrl => write!(f, "An rpc url is required"),
+            MinerError::MissingKeypair => write!(f, "A keypair is required"),
+            MinerError::InvalidThreads => write!(f, "Thread count must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for MinerError {}
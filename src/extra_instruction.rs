@@ -0,0 +1,54 @@
+use std::str::FromStr;
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Parses an instruction given as `<program_id>:<base58_data>:<accounts>`, where accounts
+/// is a comma-separated list of `pubkey[:signer][:writable]`.
+pub fn parse_instruction(spec: &str) -> Result<Instruction, String> {
+    let mut parts = spec.trim().splitn(3, ':');
+    let program_id = parts
+        .next()
+        .filter(|program_id| !program_id.is_empty())
+        .ok_or_else(|| format!("Missing program id in `{}`", spec))?;
+    let program_id =
+        Pubkey::from_str(program_id).map_err(|_| format!("Invalid program id `{}`", program_id))?;
+    let data = bs58::decode(parts.next().unwrap_or_default())
+        .into_vec()
+        .map_err(|err| format!("Invalid instruction data in `{}`: {}", spec, err))?;
+    let accounts = match parts.next() {
+        Some(accounts) if !accounts.is_empty() => accounts
+            .split(',')
+            .map(parse_account_meta)
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => vec![],
+    };
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+fn parse_account_meta(spec: &str) -> Result<AccountMeta, String> {
+    let mut parts = spec.split(':');
+    let pubkey = parts.next().unwrap_or_default();
+    let pubkey =
+        Pubkey::from_str(pubkey).map_err(|_| format!("Invalid account pubkey `{}`", pubkey))?;
+    let mut is_signer = false;
+    let mut is_writable = false;
+    for flag in parts {
+        match flag {
+            "signer" => is_signer = true,
+            "writable" => is_writable = true,
+            _ => return Err(format!("Unknown account flag `{}` in `{}`", flag, spec)),
+        }
+    }
+    Ok(AccountMeta {
+        pubkey,
+        is_signer,
+        is_writable,
+    })
+}
@@ -0,0 +1,121 @@
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use futures::{Stream, StreamExt};
+use tokio::{net::TcpListener, sync::RwLock};
+use tokio_stream::wrappers::{IntervalStream, TcpListenerStream};
+use tonic::{transport::Server, Request, Response, Status};
+use tracing::error;
+
+use crate::session::{MinerControl, MinerStats};
+
+pub mod proto {
+    tonic::include_proto!("miner");
+}
+
+use proto::{
+    miner_service_server::{MinerService, MinerServiceServer},
+    Ack, Empty, SetThreadsRequest,
+};
+
+/// Time between updates on a stats stream.
+const STREAM_INTERVAL: Duration = Duration::from_secs(1);
+
+struct MinerServiceImpl {
+    stats: Arc<RwLock<MinerStats>>,
+    control: Arc<MinerControl>,
+}
+
+#[tonic::async_trait]
+impl MinerService for MinerServiceImpl {
+    async fn get_stats(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<proto::MinerStats>, Status> {
+        let stats = self.stats.read().await;
+        Ok(Response::new(to_proto(&stats, &self.control)))
+    }
+
+    async fn set_threads(
+        &self,
+        request: Request<SetThreadsRequest>,
+    ) -> Result<Response<Ack>, Status> {
+        let threads = request.into_inner().threads;
+        if threads.eq(&0) {
+            return Err(Status::invalid_argument("threads must be at least 1"));
+        }
+        self.control.set_threads(threads);
+        Ok(ack(format!(
+            "Mining with {} threads from the next pass",
+            threads
+        )))
+    }
+
+    async fn pause_next_pass(&self, _request: Request<Empty>) -> Result<Response<Ack>, Status> {
+        self.control.set_paused(true);
+        Ok(ack("Pausing before the next pass".to_string()))
+    }
+
+    async fn resume_after_pause(&self, _request: Request<Empty>) -> Result<Response<Ack>, Status> {
+        self.control.set_paused(false);
+        Ok(ack("Resuming".to_string()))
+    }
+
+    type StreamStatsStream =
+        Pin<Box<dyn Stream<Item = Result<proto::MinerStats, Status>> + Send + 'static>>;
+
+    async fn stream_stats(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::StreamStatsStream>, Status> {
+        let stats = self.stats.clone();
+        let control = self.control.clone();
+        let stream = IntervalStream::new(tokio::time::interval(STREAM_INTERVAL)).then(move |_| {
+            let stats = stats.clone();
+            let control = control.clone();
+            async move { Ok(to_proto(&*stats.read().await, &control)) }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn to_proto(stats: &MinerStats, control: &MinerControl) -> proto::MinerStats {
+    proto::MinerStats {
+        pass: stats.pass,
+        session_ore_mined: stats.session_ore_mined,
+        session_sol_used: stats.session_sol_used,
+        best_difficulty_this_pass: stats.best_difficulty_this_pass,
+        session_best_difficulty: stats.session_best_difficulty,
+        current_sol_balance: stats.current_sol_balance,
+        current_staked_balance: stats.current_staked_balance,
+        est_ore_per_hour: stats.est_ore_per_hour,
+        uptime_secs: stats.uptime_secs,
+        paused: control.is_paused(),
+        requested_threads: control.requested_threads().unwrap_or_default(),
+    }
+}
+
+fn ack(message: String) -> Response<Ack> {
+    Response::new(Ack { message })
+}
+
+/// Serves the miner service from a background task alongside the mining loop.
+pub async fn serve_grpc(
+    port: u16,
+    stats: Arc<RwLock<MinerStats>>,
+    control: Arc<MinerControl>,
+) -> Result<(), String> {
+    let listener = TcpListener::bind(("0.0.0.0", port))
+        .await
+        .map_err(|err| err.to_string())?;
+    let service = MinerServiceServer::new(MinerServiceImpl { stats, control });
+    tokio::spawn(async move {
+        if let Err(err) = Server::builder()
+            .add_service(service)
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await
+        {
+            error!("gRPC server stopped: {}", err);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+
+const SPARKLINE_WIDTH: usize = 60;
+const SPARKLINE_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The hashrates of recent passes, scaled against the range seen over the whole session.
+#[derive(Debug, Default)]
+pub struct HashrateHistory {
+    recent: VecDeque<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl HashrateHistory {
+    pub fn record(&mut self, hashrate: f64) {
+        self.recent.push_back(hashrate);
+        while self.recent.len() > SPARKLINE_WIDTH {
+            self.recent.pop_front();
+        }
+        self.min = Some(self.min.map_or(hashrate, |min| min.min(hashrate)));
+        self.max = Some(self.max.map_or(hashrate, |max| max.max(hashrate)));
+    }
+
+    /// Renders the recent hashrates as a line of unicode blocks.
+    pub fn sparkline(&self) -> String {
+        let (Some(min), Some(max)) = (self.min, self.max) else {
+            return String::new();
+        };
+        let range = max - min;
+        self.recent
+            .iter()
+            .map(|hashrate| {
+                let level = if range.gt(&0.0) {
+                    ((hashrate - min) / range * (SPARKLINE_BLOCKS.len() - 1) as f64).round()
+                        as usize
+                } else {
+                    SPARKLINE_BLOCKS.len() - 1
+                };
+                SPARKLINE_BLOCKS[level.min(SPARKLINE_BLOCKS.len() - 1)]
+            })
+            .collect()
+    }
+}
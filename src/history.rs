@@ -0,0 +1,100 @@
+use serde_json::json;
+use solana_program::native_token::lamports_to_sol;
+use tracing::info;
+
+use crate::{
+    args::HistoryArgs,
+    db::{DailySummary, Database},
+    error::MineError,
+    output::print_json_event,
+    utils::{amount_u64_to_f64, amount_u64_to_string},
+    Miner,
+};
+
+impl Miner {
+    pub fn history(&self, args: HistoryArgs) -> Result<(), MineError> {
+        // Read daily totals
+        let days = Database::open(&args.db)
+            .and_then(|db| db.daily_summaries(args.days))
+            .map_err(|err| {
+                MineError::Unknown(format!("Failed to read database {}: {}", args.db, err))
+            })?;
+        let total = total_summary(&days);
+
+        // Print
+        if self.is_json() {
+            print_json_event(
+                "history",
+                json!({
+                    "days": days.iter().map(summary_json).collect::<Vec<_>>(),
+                    "total": summary_json(&total),
+                }),
+            );
+        } else if args.csv {
+            info!("date,passes,total_ore_mined,total_sol_spent,avg_difficulty,best_difficulty");
+            for summary in days.iter().chain(std::iter::once(&total)) {
+                info!(
+                    "{},{},{},{},{:.2},{}",
+                    summary.date,
+                    summary.passes,
+                    amount_u64_to_f64(summary.ore_mined),
+                    lamports_to_sol(summary.sol_spent),
+                    summary.avg_difficulty,
+                    summary.best_difficulty
+                );
+            }
+        } else {
+            info!(
+                "{:<10} {:>8} {:>16} {:>16} {:>15} {:>16}",
+                "Date", "Passes", "ORE mined", "SOL spent", "Avg difficulty", "Best difficulty"
+            );
+            for summary in days.iter().chain(std::iter::once(&total)) {
+                info!(
+                    "{:<10} {:>8} {:>16} {:>16} {:>15.2} {:>16}",
+                    summary.date,
+                    summary.passes,
+                    amount_u64_to_string(summary.ore_mined),
+                    lamports_to_sol(summary.sol_spent),
+                    summary.avg_difficulty,
+                    summary.best_difficulty
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn total_summary(days: &[DailySummary]) -> DailySummary {
+    let passes: u64 = days.iter().map(|summary| summary.passes).sum();
+    let difficulty_sum: f64 = days
+        .iter()
+        .map(|summary| summary.avg_difficulty * summary.passes as f64)
+        .sum();
+    DailySummary {
+        date: "Total".to_string(),
+        passes,
+        ore_mined: days.iter().map(|summary| summary.ore_mined).sum(),
+        sol_spent: days.iter().map(|summary| summary.sol_spent).sum(),
+        avg_difficulty: if passes.gt(&0) {
+            difficulty_sum / passes as f64
+        } else {
+            0.0
+        },
+        best_difficulty: days
+            .iter()
+            .map(|summary| summary.best_difficulty)
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+fn summary_json(summary: &DailySummary) -> serde_json::Value {
+    json!({
+        "date": summary.date,
+        "passes": summary.passes,
+        "total_ore_mined": amount_u64_to_f64(summary.ore_mined),
+        "total_sol_spent": lamports_to_sol(summary.sol_spent),
+        "avg_difficulty": summary.avg_difficulty,
+        "best_difficulty": summary.best_difficulty,
+    })
+}
@@ -1,25 +1,25 @@
 use ore_api::consts::TREASURY_ADDRESS;
 use solana_sdk::{signature::Signer, transaction::Transaction};
+use tracing::info;
 
-use crate::Miner;
+use crate::{error::MineError, Miner};
 
 impl Miner {
-    pub async fn initialize(&self) {
+    pub async fn initialize(&self) -> Result<(), MineError> {
         // Return early if program is already initialized
         if self.rpc_client.get_account(&TREASURY_ADDRESS).await.is_ok() {
-            return;
+            return Ok(());
         }
 
         // Submit initialize tx
-        let blockhash = self.rpc_client.get_latest_blockhash().await.unwrap();
-        let ix = ore_api::instruction::initialize(self.signer().pubkey());
-        let tx = Transaction::new_signed_with_payer(
-            &[ix],
-            Some(&self.signer().pubkey()),
-            &[&self.signer()],
-            blockhash,
-        );
-        let res = self.rpc_client.send_and_confirm_transaction(&tx).await;
-        println!("{:?}", res);
+        let signer = self.try_signer()?;
+        let blockhash = self.rpc_client.get_latest_blockhash().await?;
+        let ix = ore_api::instruction::initialize(signer.pubkey());
+        let signers: Vec<&dyn Signer> = vec![signer.as_ref()];
+        let tx =
+            Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &signers, blockhash);
+        let sig = self.rpc_client.send_and_confirm_transaction(&tx).await?;
+        info!("{}", sig);
+        Ok(())
     }
 }
@@ -0,0 +1,185 @@
+use std::{str::FromStr, time::Duration};
+
+use colored::*;
+use rand::seq::SliceRandom;
+use reqwest::Client;
+use serde_json::{json, Value};
+use solana_client::client_error::Result as ClientResult;
+use solana_program::{instruction::Instruction, pubkey::Pubkey, system_instruction};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::{error::custom_client_error, send_and_confirm::ComputeBudget, tui, Miner};
+
+const BUNDLE_RETRIES: usize = 3;
+const CONFIRM_RETRIES: usize = 20;
+const CONFIRM_DELAY: u64 = 500;
+
+/// A bundle submitted to the Jito block engine and the last status it was seen with.
+pub struct JitoBundle {
+    pub id: String,
+    pub status: String,
+}
+
+impl Miner {
+    /// Sends a transaction as a single transaction bundle to the Jito block engine, tipping
+    /// a random tip account, and polls the bundle until it lands.
+    pub async fn send_and_confirm_jito(
+        &self,
+        signer: &dyn Signer,
+        fee_payer: &dyn Signer,
+        ixs: &[Instruction],
+        compute_budget: ComputeBudget,
+        jito_url: &str,
+        tip_lamports: u64,
+    ) -> ClientResult<(Signature, JitoBundle)> {
+        let client = Client::new();
+
+        // Pick a tip account
+        let tip_accounts = jito_request(&client, jito_url, "getTipAccounts", json!([]))
+            .await
+            .map_err(custom_client_error)?;
+        let tip_account = tip_accounts
+            .as_array()
+            .and_then(|accounts| accounts.choose(&mut rand::thread_rng()))
+            .and_then(|account| account.as_str())
+            .and_then(|account| Pubkey::from_str(account).ok())
+            .ok_or_else(|| custom_client_error("No tip accounts returned".into()))?;
+
+        // Set compute budget and tip
+        let cu_limit = compute_budget.cu_limit();
+        let cu_price = match self
+            .capped_compute_unit_price(&compute_budget)
+            .await
+            .map_err(|err| custom_client_error(err.to_string()))?
+        {
+            Some(fee) => fee,
+            None => self.static_compute_unit_price(),
+        };
+        let mut final_ixs = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(cu_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(cu_price),
+        ];
+        final_ixs.extend_from_slice(ixs);
+        final_ixs.push(system_instruction::transfer(
+            &fee_payer.pubkey(),
+            &tip_account,
+            tip_lamports,
+        ));
+        let signers: Vec<&dyn Signer> = if signer.pubkey() == fee_payer.pubkey() {
+            vec![signer]
+        } else {
+            vec![signer, fee_payer]
+        };
+
+        // Submit bundle
+        let progress_bar = tui::new_progress_bar();
+        let mut last_bundle = None;
+        for attempt in 0..BUNDLE_RETRIES {
+            progress_bar.set_message(format!(
+                "Submitting bundle to Jito... (attempt {})",
+                attempt
+            ));
+            let hash = self.rpc_client.get_latest_blockhash().await?;
+            let mut tx = Transaction::new_with_payer(&final_ixs, Some(&fee_payer.pubkey()));
+            tx.sign(&signers, hash);
+            let sig = tx.signatures[0];
+            let serialized =
+                bincode::serialize(&tx).map_err(|err| custom_client_error(err.to_string()))?;
+            let encoded = bs58::encode(serialized).into_string();
+            let bundle_id = match jito_request(&client, jito_url, "sendBundle", json!([[encoded]]))
+                .await
+                .map(|id| id.as_str().map(String::from))
+            {
+                Ok(Some(bundle_id)) => bundle_id,
+                Ok(None) => continue,
+                Err(err) => {
+                    progress_bar.set_message(format!("{}: {}", "ERROR".bold().red(), err));
+                    continue;
+                }
+            };
+
+            // Poll bundle status
+            let mut bundle = JitoBundle {
+                id: bundle_id,
+                status: "pending".into(),
+            };
+            for _ in 0..CONFIRM_RETRIES {
+                tokio::time::sleep(Duration::from_millis(CONFIRM_DELAY)).await;
+                let Ok(statuses) =
+                    jito_request(&client, jito_url, "getBundleStatuses", json!([[bundle.id]]))
+                        .await
+                else {
+                    continue;
+                };
+                let status = &statuses["value"][0];
+                if status.is_null() {
+                    continue;
+                }
+                if let Some(err) = status["err"].get("Err") {
+                    bundle.status = "failed".into();
+                    progress_bar.finish_with_message(format!(
+                        "{}: Bundle {} failed: {}",
+                        "ERROR".bold().red(),
+                        bundle.id,
+                        err
+                    ));
+                    return Err(custom_client_error(format!(
+                        "Bundle {} failed: {}",
+                        bundle.id, err
+                    )));
+                }
+                if let Some(confirmation) = status["confirmation_status"].as_str() {
+                    bundle.status = confirmation.to_string();
+                    if confirmation.eq("confirmed") || confirmation.eq("finalized") {
+                        progress_bar.finish_with_message(format!(
+                            "{} {} (bundle {})",
+                            "OK".bold().green(),
+                            sig,
+                            bundle.id
+                        ));
+                        return Ok((sig, bundle));
+                    }
+                }
+            }
+            last_bundle = Some(bundle);
+        }
+
+        progress_bar.finish_with_message(format!("{}: Max retries", "ERROR".bold().red()));
+        Err(custom_client_error(match last_bundle {
+            Some(bundle) => format!("Bundle {} did not land ({})", bundle.id, bundle.status),
+            None => "Max retries".into(),
+        }))
+    }
+}
+
+/// Sends a JSON-RPC request to the block engine bundles endpoint and returns its result.
+async fn jito_request(
+    client: &Client,
+    jito_url: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value, String> {
+    let body = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": params,
+    });
+    let response: Value = client
+        .post(format!("{}/api/v1/bundles", jito_url.trim_end_matches('/')))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?
+        .json()
+        .await
+        .map_err(|err| err.to_string())?;
+    if let Some(err) = response.get("error") {
+        return Err(err.to_string());
+    }
+    Ok(response["result"].clone())
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use solana_client::{client_error::Result as ClientResult, rpc_request::RpcRequest};
+use solana_rpc_client::rpc_sender::{RpcSender, RpcTransportStats};
+
+/// An rpc sender that sleeps before every call, to exercise the cutoff, retry and watchdog
+/// logic against a slow network. Only built with the `test-utils` feature.
+pub struct SimulatedLatencyRpcClient<S> {
+    sender: S,
+    latency: Duration,
+}
+
+impl<S> SimulatedLatencyRpcClient<S> {
+    pub fn new(sender: S, latency_ms: u64) -> Self {
+        Self {
+            sender,
+            latency: Duration::from_millis(latency_ms),
+        }
+    }
+}
+
+#[async_trait]
+impl<S: RpcSender + Send + Sync> RpcSender for SimulatedLatencyRpcClient<S> {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        tokio::time::sleep(self.latency).await;
+        self.sender.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.sender.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.sender.url()
+    }
+}
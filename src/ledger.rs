@@ -0,0 +1,32 @@
+use solana_remote_wallet::{
+    locator::Locator, remote_keypair::generate_remote_keypair, remote_wallet::maybe_wallet_manager,
+};
+use solana_sdk::{derivation_path::DerivationPath, signature::Signer};
+
+use crate::{error::MineError, Miner};
+
+const NO_LEDGER: &str =
+    "No Ledger detected. Connect and unlock your Ledger, then open the Solana app.";
+
+impl Miner {
+    /// Connects to the Ledger signer at the configured derivation path.
+    pub fn ledger_signer(&self) -> Result<Box<dyn Signer>, MineError> {
+        // Parse derivation path, either absolute (m/44'/501'/0'/0') or as a key (0/0)
+        let derivation_path = match &self.ledger_derivation_path {
+            Some(path) if path.starts_with("m/") => DerivationPath::from_absolute_path_str(path),
+            Some(path) => DerivationPath::from_key_str(path),
+            None => Ok(DerivationPath::default()),
+        }
+        .map_err(|err| MineError::InvalidArgs(format!("Invalid derivation path: {}", err)))?;
+
+        // Connect to ledger
+        let Ok(Some(wallet_manager)) = maybe_wallet_manager() else {
+            return Err(MineError::Ledger(NO_LEDGER.to_string()));
+        };
+        let locator = Locator::new_from_path("usb://ledger")
+            .map_err(|err| MineError::Ledger(format!("Invalid Ledger locator: {}", err)))?;
+        generate_remote_keypair(locator, derivation_path, &wallet_manager, false, "keypair")
+            .map(|keypair| Box::new(keypair) as Box<dyn Signer>)
+            .map_err(|err| MineError::Ledger(format!("Failed to connect to Ledger: {}", err)))
+    }
+}
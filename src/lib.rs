@@ -0,0 +1,165 @@
+pub mod args;
+#[cfg(feature = "cli")]
+mod balance;
+#[cfg(feature = "cli")]
+mod benchmark;
+pub mod builder;
+pub mod bus_selector;
+mod busses;
+#[cfg(feature = "cli")]
+mod check;
+mod claim;
+#[cfg(feature = "cli")]
+mod close;
+mod config;
+mod csv_log;
+mod cu_limits;
+mod db;
+mod difficulty_histogram;
+mod dynamic_fee;
+mod earnings_rate;
+pub mod error;
+mod extra_instruction;
+mod grpc;
+mod hashrate_history;
+#[cfg(feature = "cli")]
+mod history;
+#[cfg(feature = "admin")]
+mod initialize;
+mod jito;
+#[cfg(feature = "test-utils")]
+pub mod latency;
+mod ledger;
+pub mod logging;
+mod markdown_export;
+mod metrics;
+mod mine;
+pub mod network;
+mod nonce;
+mod notify;
+mod open;
+pub mod output;
+pub mod pool;
+mod prefetch;
+mod price_feed;
+#[cfg(feature = "cli")]
+mod proof;
+mod propagation;
+mod rate_limit;
+mod rewards;
+pub mod rpc_pool;
+#[cfg(feature = "cli")]
+pub mod saved_args;
+mod schedule;
+pub mod send_and_confirm;
+mod session;
+mod shutdown;
+mod stake;
+mod system;
+mod thread_pool;
+mod topup;
+mod tpu;
+mod tui;
+#[cfg(feature = "cli")]
+mod upgrade;
+pub mod utils;
+#[cfg(feature = "cli")]
+mod verify;
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use args::{OutputFormat, PriorityFeeMode};
+use error::MineError;
+use propagation::PropagationEstimate;
+use rate_limit::RateLimitedRpcClient;
+use send_and_confirm::RetryPolicy;
+use session::MinerStats;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client::{rpc_client::RpcClientConfig, rpc_sender::RpcSender};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use tokio::sync::RwLock;
+
+/// Runs the mining commands. Built with [`builder::MinerBuilder`].
+pub struct Miner {
+    pub keypair_filepath: Option<String>,
+    pub env_keypair: Option<Keypair>,
+    pub priority_fee: Option<u64>,
+    pub lamports_per_compute_unit: Option<u64>,
+    pub dynamic_fee_url: Option<String>,
+    pub dynamic_fee_strategy: Option<String>,
+    pub priority_fee_mode: PriorityFeeMode,
+    pub priority_fee_percentile: u8,
+    pub max_priority_fee_lamports: Option<u64>,
+    pub priority_fee_cap_per_pass: Option<u64>,
+    pub max_retries: usize,
+    pub retry_policy: RetryPolicy,
+    pub min_sol_balance: f64,
+    pub rpc_client: Arc<RpcClient>,
+    pub fee_payer_filepath: Option<String>,
+    pub output: OutputFormat,
+    pub ledger: bool,
+    pub ledger_derivation_path: Option<String>,
+    pub stats: Arc<RwLock<MinerStats>>,
+    pub propagation: Mutex<PropagationEstimate>,
+}
+
+/// Creates an rpc client that sends requests through the given sender, paced to the rate limit
+/// if one is set.
+pub fn new_rpc_client<S: RpcSender + Send + Sync + 'static>(
+    sender: S,
+    ratelimit_rpc: Option<u32>,
+    output: OutputFormat,
+) -> RpcClient {
+    let config = RpcClientConfig::with_commitment(CommitmentConfig::confirmed());
+    match ratelimit_rpc {
+        Some(calls_per_second) => RpcClient::new_sender(
+            RateLimitedRpcClient::new(sender, calls_per_second, output),
+            config,
+        ),
+        None => RpcClient::new_sender(sender, config),
+    }
+}
+
+impl Miner {
+    /// Returns the state of the current mining session.
+    pub async fn stats(&self) -> MinerStats {
+        self.stats.read().await.clone()
+    }
+
+    /// Loads the signer, failing instead of panicking if its keypair cannot be read.
+    pub fn try_signer(&self) -> Result<Box<dyn Signer>, MineError> {
+        if self.ledger {
+            return self.ledger_signer();
+        }
+        if let Some(keypair) = &self.env_keypair {
+            return Ok(Box::new(keypair.insecure_clone()));
+        }
+        match &self.keypair_filepath {
+            Some(filepath) => load_keypair(filepath).map(|keypair| Box::new(keypair) as _),
+            None => Err(MineError::InvalidArgs("No keypair provided".to_string())),
+        }
+    }
+
+    /// Loads the fee payer, failing instead of panicking if its keypair cannot be read.
+    pub fn try_fee_payer(&self) -> Result<Box<dyn Signer>, MineError> {
+        match &self.fee_payer_filepath {
+            Some(filepath) => load_keypair(filepath).map(|keypair| Box::new(keypair) as _),
+            None => self.try_signer(),
+        }
+    }
+}
+
+fn load_keypair(filepath: &str) -> Result<Keypair, MineError> {
+    read_keypair_file(filepath).map_err(|err| {
+        MineError::KeypairLoad(io::Error::new(
+            io::ErrorKind::Other,
+            format!("No keypair found at {}: {}", filepath, err),
+        ))
+    })
+}
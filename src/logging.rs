@@ -0,0 +1,66 @@
+use std::{fmt, io::Write};
+
+use colored::*;
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    filter::LevelFilter, layer::Context, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
+
+use crate::args::{LogFormat, LogLevel};
+
+/// Prints each event's message on stdout the way the miner always has, flagging warnings
+/// and errors.
+struct HumanLayer;
+
+impl<S: Subscriber> Layer<S> for HumanLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        let line = match *event.metadata().level() {
+            Level::ERROR => format!("{} {}", "ERROR".bold().red(), message.0),
+            Level::WARN => format!("{} {}", "WARNING".bold().yellow(), message.0),
+            Level::INFO => message.0,
+            _ => message.0.dimmed().to_string(),
+        };
+        writeln!(std::io::stdout().lock(), "{}", line).ok();
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name().eq("message") {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+/// Installs the global subscriber. Colors are turned off if asked to, or unless stdout is a
+/// terminal that supports them.
+pub fn init(level: LogLevel, format: LogFormat, no_color: bool) {
+    if no_color
+        || format.eq(&LogFormat::Json)
+        || supports_color::on(supports_color::Stream::Stdout).is_none()
+    {
+        colored::control::set_override(false);
+    }
+    let filter = LevelFilter::from_level(level.into());
+    match format {
+        LogFormat::Human => tracing_subscriber::registry()
+            .with(HumanLayer.with_filter(filter))
+            .init(),
+        LogFormat::Json => tracing_subscriber::registry()
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_writer(std::io::stdout)
+                    .with_filter(filter),
+            )
+            .init(),
+    }
+}
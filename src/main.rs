@@ -1,41 +1,22 @@
-mod args;
-mod balance;
-mod benchmark;
-mod busses;
-mod claim;
-mod close;
-mod config;
-mod cu_limits;
-mod dynamic_fee;
-#[cfg(feature = "admin")]
-mod initialize;
-mod mine;
-mod open;
-mod proof;
-mod rewards;
-mod send_and_confirm;
-mod stake;
-mod upgrade;
-mod utils;
+mod miner_config;
 
 use std::sync::Arc;
 
-use args::*;
-use clap::{command, Parser, Subcommand};
-use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    signature::{read_keypair_file, Keypair},
+use clap::{command, CommandFactory, FromArgMatches, Parser, Subcommand};
+use miner_config::MinerConfig;
+#[cfg(feature = "test-utils")]
+use ore_cli::latency::SimulatedLatencyRpcClient;
+use ore_cli::{
+    args::{self, *},
+    builder::MinerBuilder,
+    logging, new_rpc_client, output,
+    rpc_pool::RpcClientPool,
+    send_and_confirm::{self, RetryPolicy, MIN_SOL_BALANCE},
+    utils::{read_keypair_command, read_keypair_env},
 };
-
-struct Miner {
-    pub keypair_filepath: Option<String>,
-    pub priority_fee: Option<u64>,
-    pub dynamic_fee_url: Option<String>,
-    pub dynamic_fee_strategy: Option<String>,
-    pub rpc_client: Arc<RpcClient>,
-    pub fee_payer_filepath: Option<String>,
-}
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client::{http_sender::HttpSender, rpc_sender::RpcSender};
+use tracing::{error, info};
 
 #[derive(Subcommand, Debug)]
 enum Commands {
@@ -45,9 +26,15 @@ enum Commands {
     #[command(about = "Benchmark your hashpower")]
     Benchmark(BenchmarkArgs),
 
+    #[command(about = "Compare hashpower across thread counts")]
+    BenchmarkThreads(BenchmarkThreadsArgs),
+
     #[command(about = "Fetch the bus account balances")]
     Busses(BussesArgs),
 
+    #[command(about = "Check the mining setup without submitting any transactions")]
+    Check(CheckArgs),
+
     #[command(about = "Claim your mining rewards")]
     Claim(ClaimArgs),
 
@@ -57,21 +44,36 @@ enum Commands {
     #[command(about = "Fetch the program config")]
     Config(ConfigArgs),
 
+    #[command(about = "Summarize the recorded passes by day")]
+    History(HistoryArgs),
+
     #[command(about = "Start mining")]
     Mine(MineArgs),
 
+    #[command(about = "Mine with a pool")]
+    Pool(PoolArgs),
+
     #[command(about = "Fetch a proof account by address")]
     Proof(ProofArgs),
 
+    #[command(about = "Register a proof account to mine with")]
+    Register(RegisterArgs),
+
     #[command(about = "Fetch the current reward rate for each difficulty level")]
     Rewards(RewardsArgs),
 
+    #[command(about = "Save mine options to a file to load with mine --args-file")]
+    SaveConfig(SaveConfigArgs),
+
     #[command(about = "Stake to earn a rewards multiplier")]
     Stake(StakeArgs),
 
     #[command(about = "Upgrade your ORE tokens from v1 to v2")]
     Upgrade(UpgradeArgs),
 
+    #[command(about = "Check whether a submitted solution landed and what it earned")]
+    Verify(VerifyArgs),
+
     #[cfg(feature = "admin")]
     #[command(about = "Initialize the program")]
     Initialize(InitializeArgs),
@@ -88,6 +90,23 @@ struct Args {
     )]
     rpc: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "NETWORK",
+        help = "Well-known network to use the RPC provider of. --rpc takes precedence. Only the ORE program this binary was built with is supported.",
+        global = true
+    )]
+    network: Option<Network>,
+
+    #[arg(
+        long,
+        value_name = "NETWORK_URLS",
+        help = "Comma-separated list of RPC providers to fail over between",
+        value_delimiter = ',',
+        global = true
+    )]
+    rpc_urls: Vec<String>,
+
     #[clap(
         global = true,
         short = 'C',
@@ -97,6 +116,22 @@ struct Args {
     )]
     config_file: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Filepath to a TOML file of miner options. Defaults to ~/.config/ore-miner/config.toml",
+        global = true
+    )]
+    miner_config: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Write the effective miner options to a TOML file and exit",
+        global = true
+    )]
+    generate_config: Option<String>,
+
     #[arg(
         long,
         value_name = "KEYPAIR_FILEPATH",
@@ -105,6 +140,41 @@ struct Args {
     )]
     keypair: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "VARIABLE",
+        help = "Environment variable holding a base58 or JSON array encoded keypair to use",
+        conflicts_with = "keypair",
+        global = true
+    )]
+    keypair_env: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "COMMAND",
+        help = "Shell command that prints a base58 or JSON array encoded keypair to use, such as a password manager's cli",
+        conflicts_with_all = ["keypair", "keypair_env"],
+        global = true
+    )]
+    keypair_command: Option<String>,
+
+    #[arg(
+        long,
+        help = "Sign transactions with a Ledger hardware wallet",
+        conflicts_with_all = ["keypair", "keypair_env", "keypair_command"],
+        global = true
+    )]
+    ledger: bool,
+
+    #[arg(
+        long,
+        value_name = "DERIVATION_PATH",
+        help = "Derivation path of the Ledger account to use, e.g. 0/0",
+        requires = "ledger",
+        global = true
+    )]
+    ledger_derivation_path: Option<String>,
+
     #[arg(
         long,
         value_name = "FEE_PAYER_FILEPATH",
@@ -122,6 +192,15 @@ struct Args {
     )]
     priority_fee: Option<u64>,
 
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Static price to pay per compute unit on every transaction, in place of the priority fee. Dynamic fees still take precedence.",
+        conflicts_with = "priority_fee",
+        global = true
+    )]
+    lamports_per_compute_unit: Option<u64>,
+
     #[arg(
         long,
         value_name = "DYNAMIC_FEE_URL",
@@ -138,18 +217,153 @@ struct Args {
     )]
     dynamic_fee_strategy: Option<String>,
 
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Whether to pay a fixed priority fee, or one based on recently landed transactions",
+        default_value = "fixed",
+        global = true
+    )]
+    priority_fee_mode: PriorityFeeMode,
+
+    #[arg(
+        long,
+        value_name = "PERCENTILE",
+        help = "Percentile of recent prioritization fees to pay in dynamic priority fee mode",
+        default_value = "75",
+        value_parser = clap::value_parser!(u8).range(0..=100),
+        global = true
+    )]
+    priority_fee_percentile: u8,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Cap in lamports on the priority fee the --priority-fee-percentile estimate of recent fees may reach. Only used with --priority-fee-mode dynamic.",
+        global = true
+    )]
+    max_priority_fee_lamports: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "LAMPORTS",
+        help = "Cap in lamports on the priority fee of any estimated fee, including --dynamic-fee-strategy ones, with a warning when it lowers the fee. Applied after --max-priority-fee-lamports, so the lower cap wins.",
+        global = true
+    )]
+    priority_fee_cap_per_pass: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "CALLS_PER_SECOND",
+        help = "Maximum number of rpc calls to make per second",
+        value_parser = clap::value_parser!(u32).range(1..),
+        global = true
+    )]
+    ratelimit_rpc: Option<u32>,
+
+    #[command(flatten)]
+    test_utils: TestUtilsArgs,
+
+    #[arg(
+        long,
+        value_name = "RETRIES",
+        help = "Number of times to rebroadcast a transaction that does not confirm",
+        default_value = "5",
+        global = true
+    )]
+    max_retries: usize,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "How to rebroadcast transactions until they confirm",
+        default_value = "backoff",
+        global = true
+    )]
+    submit_retry_policy: RetryPolicy,
+
+    #[arg(
+        long,
+        value_name = "SOL",
+        help = "Stop before submitting when the fee payer holds no more than this much SOL",
+        default_value_t = MIN_SOL_BALANCE,
+        value_parser = parse_min_sol_balance,
+        global = true
+    )]
+    min_sol_balance: f64,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help = "Format to print output in",
+        default_value = "human",
+        global = true
+    )]
+    output: OutputFormat,
+
+    #[arg(
+        long,
+        help = "Indent each JSON event across multiple lines. Only valid with --output json. Not compatible with parsers that expect one event per line.",
+        global = true
+    )]
+    log_json_pretty: bool,
+
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        help = "Minimum severity of messages to print",
+        default_value = "info",
+        global = true
+    )]
+    log_level: LogLevel,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help = "Format to print log messages in",
+        default_value = "human",
+        global = true
+    )]
+    log_format: LogFormat,
+
+    #[arg(long, help = "Print output without colors", global = true)]
+    no_color: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[tokio::main]
 async fn main() {
-    let args = Args::parse();
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    logging::init(args.log_level, args.log_format, args.no_color);
+    if args.log_json_pretty {
+        if args.output.ne(&OutputFormat::Json) {
+            error!("--log-json-pretty requires --output json");
+            std::process::exit(1);
+        }
+        output::set_json_pretty(true);
+    }
+
+    // Fill in options from the config files
+    if let Err(err) = miner_config::load(&mut args, &matches) {
+        error!("{}", err);
+        std::process::exit(1);
+    }
+    if let Some(path) = &args.generate_config {
+        if let Err(err) = MinerConfig::from_args(&args).write(path) {
+            error!("Could not write config file `{}`: {}", path, err);
+            std::process::exit(1);
+        }
+        info!("Wrote config to {}", path);
+        return;
+    }
 
     // Load the config file from custom path, the default path, or use default config values
     let cli_config = if let Some(config_file) = &args.config_file {
         solana_cli_config::Config::load(config_file).unwrap_or_else(|_| {
-            eprintln!("error: Could not find config file `{}`", config_file);
+            error!("Could not find config file `{}`", config_file);
             std::process::exit(1);
         })
     } else if let Some(config_file) = &*solana_cli_config::CONFIG_FILE {
@@ -159,94 +373,127 @@ async fn main() {
     };
 
     // Initialize miner.
-    let cluster = args.rpc.unwrap_or(cli_config.json_rpc_url);
+    let network_url = match args.network.map(|network| network.config()).transpose() {
+        Ok(network) => network.map(|network| network.rpc_url),
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    };
+    let cluster = args.rpc.or(network_url).unwrap_or(cli_config.json_rpc_url);
     let default_keypair = args.keypair.unwrap_or(cli_config.keypair_path.clone());
-    let fee_payer_filepath = args.fee_payer.unwrap_or(default_keypair.clone());
-    let rpc_client = RpcClient::new_with_commitment(cluster, CommitmentConfig::confirmed());
-
-    let miner = Arc::new(Miner::new(
-        Arc::new(rpc_client),
-        args.priority_fee,
-        Some(default_keypair),
-        args.dynamic_fee_url,
-        args.dynamic_fee_strategy,
-        Some(fee_payer_filepath),
-    ));
+    let env_keypair = args
+        .keypair_env
+        .as_deref()
+        .map(read_keypair_env)
+        .or_else(|| args.keypair_command.as_deref().map(read_keypair_command))
+        .map(|keypair| {
+            keypair.unwrap_or_else(|err| {
+                error!("{}", err);
+                std::process::exit(1);
+            })
+        });
+    let rpc_client = if args.rpc_urls.is_empty() {
+        build_rpc_client(HttpSender::new(cluster), &args)
+    } else {
+        let urls = std::iter::once(cluster)
+            .chain(args.rpc_urls.clone())
+            .collect();
+        build_rpc_client(RpcClientPool::new(urls), &args)
+    };
+
+    let mut builder = MinerBuilder::new()
+        .rpc_client(Arc::new(rpc_client))
+        .keypair_filepath(default_keypair)
+        .priority_fee_mode(args.priority_fee_mode)
+        .priority_fee_percentile(args.priority_fee_percentile)
+        .max_retries(args.max_retries)
+        .retry_policy(args.submit_retry_policy)
+        .min_sol_balance(args.min_sol_balance)
+        .output(args.output);
+    if let Some(keypair) = env_keypair {
+        builder = builder.keypair(keypair);
+    }
+    if let Some(path) = args.fee_payer {
+        builder = builder.fee_payer_filepath(path);
+    }
+    if args.ledger {
+        builder = builder.ledger(args.ledger_derivation_path);
+    }
+    if let Some(priority_fee) = args.priority_fee {
+        builder = builder.priority_fee(priority_fee);
+    }
+    if let Some(lamports) = args.lamports_per_compute_unit {
+        builder = builder.lamports_per_compute_unit(lamports);
+    }
+    if let Some(url) = args.dynamic_fee_url {
+        builder = builder.dynamic_fee_url(url);
+    }
+    if let Some(strategy) = args.dynamic_fee_strategy {
+        builder = builder.dynamic_fee_strategy(strategy);
+    }
+    if let Some(lamports) = args.max_priority_fee_lamports {
+        builder = builder.max_priority_fee_lamports(lamports);
+    }
+    if let Some(lamports) = args.priority_fee_cap_per_pass {
+        builder = builder.priority_fee_cap_per_pass(lamports);
+    }
+    let miner = Arc::new(builder.build().unwrap_or_else(|err| {
+        error!("{}", err);
+        std::process::exit(1);
+    }));
 
     // Execute user command.
-    match args.command {
-        Commands::Balance(args) => {
-            miner.balance(args).await;
-        }
-        Commands::Benchmark(args) => {
-            miner.benchmark(args).await;
-        }
-        Commands::Busses(_) => {
-            miner.busses().await;
-        }
-        Commands::Claim(args) => {
-            miner.claim(args).await;
-        }
-        Commands::Close(_) => {
-            miner.close().await;
-        }
-        Commands::Config(_) => {
-            miner.config().await;
-        }
-        Commands::Mine(args) => {
-            miner.mine(args).await;
-        }
-        Commands::Proof(args) => {
-            miner.proof(args).await;
-        }
-        Commands::Rewards(_) => {
-            miner.rewards().await;
-        }
-        Commands::Stake(args) => {
-            miner.stake(args).await;
-        }
-        Commands::Upgrade(args) => {
-            miner.upgrade(args).await;
-        }
+    let result = match args.command {
+        Commands::Balance(args) => miner.balance(args).await,
+        Commands::Benchmark(args) => miner.benchmark(args).await,
+        Commands::BenchmarkThreads(args) => miner.benchmark_threads(args).await,
+        Commands::Busses(_) => miner.busses().await,
+        Commands::Check(args) => miner.check(args).await,
+        Commands::Claim(args) => miner.claim(args).await,
+        Commands::Close(_) => miner.close().await,
+        Commands::Config(_) => miner.config().await,
+        Commands::History(args) => miner.history(args),
+        Commands::Mine(args) => miner.mine(args.into()).await,
+        Commands::Pool(args) => miner.pool(args).await,
+        Commands::Proof(args) => miner.proof(args).await,
+        Commands::Register(_) => miner.register().await,
+        Commands::Rewards(_) => miner.rewards().await,
+        Commands::SaveConfig(args) => miner.save_config(args),
+        Commands::Stake(args) => miner.stake(args).await,
+        Commands::Upgrade(args) => miner.upgrade(args).await,
+        Commands::Verify(args) => miner.verify(args).await,
         #[cfg(feature = "admin")]
-        Commands::Initialize(_) => {
-            miner.initialize().await;
-        }
+        Commands::Initialize(_) => miner.initialize().await,
+    };
+    if let Err(err) = result {
+        error!("{}", err);
+        std::process::exit(1);
     }
 }
 
-impl Miner {
-    pub fn new(
-        rpc_client: Arc<RpcClient>,
-        priority_fee: Option<u64>,
-        keypair_filepath: Option<String>,
-        dynamic_fee_url: Option<String>,
-        dynamic_fee_strategy: Option<String>,
-        fee_payer_filepath: Option<String>,
-    ) -> Self {
-        Self {
-            rpc_client,
-            keypair_filepath,
-            priority_fee,
-            dynamic_fee_url,
-            dynamic_fee_strategy,
-            fee_payer_filepath,
-        }
-    }
-
-    pub fn signer(&self) -> Keypair {
-        match self.keypair_filepath.clone() {
-            Some(filepath) => read_keypair_file(filepath.clone())
-                .expect(format!("No keypair found at {}", filepath).as_str()),
-            None => panic!("No keypair provided"),
+/// Creates the rpc client for the command line options, delaying every call if a slow network
+/// is simulated.
+#[cfg(feature = "test-utils")]
+fn build_rpc_client<S: RpcSender + Send + Sync + 'static>(sender: S, args: &Args) -> RpcClient {
+    match args.test_utils.simulate_network_latency {
+        Some(latency_ms) => {
+            output::print_warning(
+                args.output,
+                &format!("Simulating {} ms of latency on every rpc call", latency_ms),
+            );
+            new_rpc_client(
+                SimulatedLatencyRpcClient::new(sender, latency_ms),
+                args.ratelimit_rpc,
+                args.output,
+            )
         }
+        None => new_rpc_client(sender, args.ratelimit_rpc, args.output),
     }
+}
 
-    pub fn fee_payer(&self) -> Keypair {
-        match self.fee_payer_filepath.clone() {
-            Some(filepath) => read_keypair_file(filepath.clone())
-                .expect(format!("No fee payer keypair found at {}", filepath).as_str()),
-            None => panic!("No fee payer keypair provided"),
-        }
-    }
+/// Creates the rpc client for the command line options.
+#[cfg(not(feature = "test-utils"))]
+fn build_rpc_client<S: RpcSender + Send + Sync + 'static>(sender: S, args: &Args) -> RpcClient {
+    new_rpc_client(sender, args.ratelimit_rpc, args.output)
 }
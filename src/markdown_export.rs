@@ -0,0 +1,86 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use solana_program::{native_token::lamports_to_sol, pubkey::Pubkey};
+
+use crate::{
+    csv_log::{CsvRow, HEADER},
+    difficulty_histogram::DifficultyHistogram,
+    session::WalletStats,
+    utils::amount_u64_to_string,
+};
+
+/// Collects the rows written to the csv log, to export the session as Markdown tables once
+/// it ends.
+pub struct MarkdownExport {
+    path: String,
+    started_at: DateTime<Utc>,
+    rows: Vec<(Pubkey, DateTime<Utc>, CsvRow)>,
+}
+
+impl MarkdownExport {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            started_at: Utc::now(),
+            rows: vec![],
+        }
+    }
+
+    pub fn record(&mut self, wallet: Pubkey, row: CsvRow) {
+        self.rows.push((wallet, Utc::now(), row));
+    }
+
+    /// Writes the session header, totals and pass history to the export path.
+    pub fn write(
+        &self,
+        session_id: &str,
+        wallets: &[Pubkey],
+        totals: WalletStats,
+        difficulties: &DifficultyHistogram,
+    ) -> std::io::Result<()> {
+        let mut md = String::new();
+        writeln!(md, "# ORE mining session {}\n", session_id).ok();
+        for wallet in wallets {
+            writeln!(md, "- Wallet: `{}`", wallet).ok();
+        }
+        writeln!(md, "- Started: {}", self.started_at.to_rfc3339()).ok();
+        writeln!(md, "- Ended: {}\n", Utc::now().to_rfc3339()).ok();
+
+        // Totals
+        writeln!(md, "## Summary\n").ok();
+        writeln!(md, "| passes | ore_mined | sol_cost | ore_staked |").ok();
+        writeln!(md, "| --- | --- | --- | --- |").ok();
+        writeln!(
+            md,
+            "| {} | {} | {} | {} |\n",
+            totals.passes,
+            amount_u64_to_string(totals.ore_mined),
+            lamports_to_sol(totals.sol_used),
+            amount_u64_to_string(totals.ore_staked)
+        )
+        .ok();
+
+        // Difficulty distribution
+        writeln!(md, "## Difficulty histogram\n").ok();
+        writeln!(md, "```").ok();
+        for line in difficulties.lines() {
+            writeln!(md, "{}", line).ok();
+        }
+        writeln!(md, "```\n").ok();
+
+        // Pass history, with the csv log's columns
+        let columns: Vec<&str> = std::iter::once("wallet").chain(HEADER.split(',')).collect();
+        writeln!(md, "## Passes\n").ok();
+        writeln!(md, "| {} |", columns.join(" | ")).ok();
+        writeln!(md, "|{}", " --- |".repeat(columns.len())).ok();
+        for (wallet, timestamp, row) in &self.rows {
+            let fields: Vec<String> = [wallet.to_string(), timestamp.to_rfc3339()]
+                .into_iter()
+                .chain(row.fields())
+                .collect();
+            writeln!(md, "| {} |", fields.join(" | ")).ok();
+        }
+        std::fs::write(&self.path, md)
+    }
+}
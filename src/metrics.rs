@@ -0,0 +1,145 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Arc, Mutex},
+};
+
+use tiny_http::{Header, Response, Server};
+
+use crate::system::SystemStats;
+
+#[derive(Clone, Debug, Default)]
+pub struct WalletMetrics {
+    pub session_ore_total: f64,
+    pub session_sol_cost_total: f64,
+    pub pass_duration_seconds: f64,
+    pub best_difficulty: u32,
+    pub sol_balance: f64,
+    pub staked_balance: f64,
+    pub est_ore_per_hour: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct MetricsState {
+    pub wallets: BTreeMap<String, WalletMetrics>,
+    pub cpu_temp: Option<f32>,
+    pub load_avg_1m: Option<f32>,
+    pub load_avg_5m: Option<f32>,
+    pub load_avg_15m: Option<f32>,
+    pub watchdog_timeouts: u64,
+}
+
+impl MetricsState {
+    pub fn update(&mut self, wallet: String, metrics: WalletMetrics, system_stats: &SystemStats) {
+        self.wallets.insert(wallet, metrics);
+        self.cpu_temp = system_stats.cpu_temp;
+        self.load_avg_1m = system_stats.load_avg_1m;
+        self.load_avg_5m = system_stats.load_avg_5m;
+        self.load_avg_15m = system_stats.load_avg_15m;
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let wallet_metrics: [(&str, &str, &str, fn(&WalletMetrics) -> f64); 7] = [
+            (
+                "ore_miner_session_ore_total",
+                "counter",
+                "ORE mined this session",
+                |m| m.session_ore_total,
+            ),
+            (
+                "ore_miner_session_sol_cost_total",
+                "counter",
+                "SOL spent on fees this session",
+                |m| m.session_sol_cost_total,
+            ),
+            (
+                "ore_miner_pass_duration_seconds",
+                "gauge",
+                "Duration of the last mining pass",
+                |m| m.pass_duration_seconds,
+            ),
+            (
+                "ore_miner_best_difficulty",
+                "gauge",
+                "Best difficulty found in the last mining pass",
+                |m| m.best_difficulty as f64,
+            ),
+            (
+                "ore_miner_sol_balance",
+                "gauge",
+                "SOL balance of the fee payer",
+                |m| m.sol_balance,
+            ),
+            (
+                "ore_miner_staked_balance",
+                "gauge",
+                "Staked ORE balance",
+                |m| m.staked_balance,
+            ),
+            (
+                "ore_miner_est_ore_per_hour",
+                "gauge",
+                "Estimated ORE mined per hour, from a moving average of recent passes",
+                |m| m.est_ore_per_hour,
+            ),
+        ];
+        for (name, kind, help, value) in wallet_metrics {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+            for (wallet, metrics) in self.wallets.iter() {
+                let _ = writeln!(out, "{}{{wallet=\"{}\"}} {}", name, wallet, value(metrics));
+            }
+        }
+        if let Some(cpu_temp) = self.cpu_temp {
+            let _ = writeln!(
+                out,
+                "# HELP ore_miner_cpu_temp_celsius CPU temperature\n# TYPE ore_miner_cpu_temp_celsius gauge\nore_miner_cpu_temp_celsius {}",
+                cpu_temp
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP ore_miner_watchdog_timeouts_total Passes restarted by the watchdog\n# TYPE ore_miner_watchdog_timeouts_total counter\nore_miner_watchdog_timeouts_total {}",
+            self.watchdog_timeouts
+        );
+        let _ = writeln!(
+            out,
+            "# HELP ore_miner_load_avg System load average\n# TYPE ore_miner_load_avg gauge"
+        );
+        for (period, load_avg) in [
+            ("1m", self.load_avg_1m),
+            ("5m", self.load_avg_5m),
+            ("15m", self.load_avg_15m),
+        ] {
+            if let Some(load_avg) = load_avg {
+                let _ = writeln!(
+                    out,
+                    "ore_miner_load_avg{{period=\"{}\"}} {}",
+                    period, load_avg
+                );
+            }
+        }
+        out
+    }
+}
+
+/// Serves the metrics on /metrics from a background thread.
+pub fn serve_metrics(port: u16, state: Arc<Mutex<MetricsState>>) -> Result<(), String> {
+    let server = Server::http(("0.0.0.0", port)).map_err(|err| err.to_string())?;
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = if request.url().eq("/metrics") {
+                let body = state.lock().unwrap().render();
+                Response::from_string(body).with_header(
+                    Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..])
+                        .unwrap(),
+                )
+            } else {
+                Response::from_string("Not found").with_status_code(404)
+            };
+            let _ = request.respond(response);
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,594 @@
+use std::{
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+};
+
+use clap::{parser::ValueSource, Arg, ArgMatches, Command, CommandFactory, FromArgMatches};
+use ore_cli::saved_args::is_json;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    args::{
+        ClockDriftAction, CpuGovernor, InsufficientSolPolicy, LowDifficultyPolicy, MineArgs,
+        Network, NonceStrategy, PriorityFeeMode, ThreadPriorityLevel,
+    },
+    send_and_confirm::RetryPolicy,
+    Args, Commands,
+};
+
+/// Config keys named differently from the arg they set.
+const RENAMED_KEYS: &[(&str, &str)] = &[
+    ("rpc_url", "rpc"),
+    ("keypair_path", "keypair"),
+    ("fee_payer_path", "fee_payer"),
+    ("threads", "cores"),
+    ("extra_instructions", "extra_instruction"),
+];
+
+/// Fills in the options not passed on the command line from the config files. Each option takes
+/// the first value found in:
+///
+/// 1. the command line
+/// 2. the file given to `mine --args-file`, as written by `save-config`
+/// 3. the `--miner-config` file, or `~/.config/ore-miner/config.toml` if it exists
+/// 4. the default of its flag
+///
+/// Values from the files are handed to clap as flags, so they go through the same parsers and
+/// `requires`/`conflicts_with` checks as options passed on the command line.
+pub fn load(args: &mut Args, matches: &ArgMatches) -> Result<(), String> {
+    let mut files = vec![];
+    if let Some(miner_config) = MinerConfig::load(args.miner_config.as_deref())? {
+        files.push(miner_config);
+    }
+    if let Commands::Mine(mine_args) = &args.command {
+        if let Some(path) = &mine_args.args_file {
+            files.push(FileOptions::read_args_file(path)?);
+        }
+    }
+    if let Some(merged) = merge(std::env::args_os().collect(), matches, &files)? {
+        *args = merged;
+    }
+    Ok(())
+}
+
+/// Options read from a config file, keyed by the name the file gives them.
+struct FileOptions {
+    path: String,
+    values: Map<String, Value>,
+}
+
+impl FileOptions {
+    /// Reads the mine options saved in a file, which are named after their args.
+    fn read_args_file(path: &str) -> Result<Self, String> {
+        let error = |err: String| format!("Could not load mine options from `{}`: {}", path, err);
+        let contents = std::fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let saved: Value = if is_json(path) {
+            serde_json::from_str(&contents).map_err(|err| error(err.to_string()))?
+        } else {
+            toml::from_str(&contents).map_err(|err| error(err.to_string()))?
+        };
+        let Value::Object(values) = saved else {
+            return Err(error("Expected a table of mine options".to_string()));
+        };
+        Ok(Self {
+            path: path.to_string(),
+            values,
+        })
+    }
+}
+
+/// Re-parses the command line with the options from the files appended, each file overriding
+/// the ones before it. Returns `None` if the files set nothing.
+fn merge(
+    argv: Vec<OsString>,
+    matches: &ArgMatches,
+    files: &[FileOptions],
+) -> Result<Option<Args>, String> {
+    let mut command = Args::command();
+    command.build();
+    let mine = matches
+        .subcommand_matches("mine")
+        .and_then(|mine_matches| Some((command.find_subcommand("mine")?, mine_matches)));
+
+    // Flags to append, keyed by arg id so a later file replaces an earlier one
+    let mut flags: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        for (key, value) in &file.values {
+            let id = RENAMED_KEYS
+                .iter()
+                .find(|(renamed, _)| key.eq(renamed))
+                .map_or(key.as_str(), |(_, id)| *id);
+            let global = command.get_arguments().find(|arg| arg.get_id().eq(id));
+            let (cmd, arg, arg_matches) = match (global, mine) {
+                (Some(arg), _) => (&command, arg, matches),
+                (None, Some((mine_command, mine_matches))) => {
+                    match mine_command.get_arguments().find(|arg| arg.get_id().eq(id)) {
+                        Some(arg) => (mine_command, arg, mine_matches),
+                        None => return Err(format!("Unknown option `{}` in `{}`", key, file.path)),
+                    }
+                }
+                // Mine options only apply to the mine command
+                (None, None)
+                    if MineArgs::command()
+                        .get_arguments()
+                        .any(|arg| arg.get_id().eq(id)) =>
+                {
+                    continue
+                }
+                (None, None) => return Err(format!("Unknown option `{}` in `{}`", key, file.path)),
+            };
+            let from_cli = arg_matches
+                .value_source(id)
+                .map_or(false, |source| source.eq(&ValueSource::CommandLine));
+            if from_cli {
+                continue;
+            }
+            let tokens = to_flags(cmd, arg, value)
+                .map_err(|err| format!("Invalid `{}` in `{}`: {}", key, file.path, err))?;
+            flags.insert(id.to_string(), tokens);
+        }
+    }
+    if flags.values().all(Vec::is_empty) {
+        return Ok(None);
+    }
+
+    let argv = argv
+        .into_iter()
+        .chain(flags.into_values().flatten().map(OsString::from));
+    let merged = command.try_get_matches_from(argv).map_err(|err| {
+        format!(
+            "Invalid options in the config files: {}",
+            clap_message(&err)
+        )
+    })?;
+    Args::from_arg_matches(&merged)
+        .map(Some)
+        .map_err(|err| clap_message(&err))
+}
+
+/// Returns the command line tokens that set an arg to a file value, after checking the value
+/// with the arg's parser.
+fn to_flags(cmd: &Command, arg: &Arg, value: &Value) -> Result<Vec<String>, String> {
+    let long = arg
+        .get_long()
+        .ok_or_else(|| "cannot be set from a file".to_string())?;
+    if !arg.get_action().takes_values() {
+        return match value {
+            Value::Bool(true) => Ok(vec![format!("--{}", long)]),
+            Value::Bool(false) | Value::Null => Ok(vec![]),
+            _ => Err("expected true or false".to_string()),
+        };
+    }
+    let values = match value {
+        Value::Null => vec![],
+        Value::Array(values) => values.iter().collect(),
+        value => vec![value],
+    };
+    let values = values
+        .into_iter()
+        .map(|value| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                Value::Number(value) => value.to_string(),
+                Value::Bool(value) => value.to_string(),
+                _ => return Err("expected a string, number or boolean".to_string()),
+            };
+            arg.get_value_parser()
+                .parse_ref(cmd, Some(arg), OsStr::new(&value))
+                .map_err(|err| clap_message(&err))?;
+            Ok(value)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    // Saved files spell out every default, which clap must keep treating as unset so they
+    // don't trip `requires` checks
+    let defaults = arg.get_default_values();
+    let is_default = !defaults.is_empty()
+        && defaults.len().eq(&values.len())
+        && defaults
+            .iter()
+            .zip(&values)
+            .all(|(default, value)| same_value(&default.to_string_lossy(), value));
+    if is_default {
+        return Ok(vec![]);
+    }
+    Ok(values
+        .into_iter()
+        .map(|value| format!("--{}={}", long, value))
+        .collect())
+}
+
+/// Whether two option values are equal, comparing numbers by value so `1` matches `1.0`.
+fn same_value(a: &str, b: &str) -> bool {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.eq(&b),
+        _ => a.eq(b),
+    }
+}
+
+/// Returns the first line of a clap error, without its `error: ` prefix.
+fn clap_message(err: &clap::Error) -> String {
+    let rendered = err.to_string();
+    let line = rendered.lines().next().unwrap_or_default();
+    line.strip_prefix("error: ").unwrap_or(line).to_string()
+}
+
+/// Miner options read from a TOML file, covering the global options as well as the mine options.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MinerConfig {
+    pub rpc_url: Option<String>,
+    pub network: Option<Network>,
+    pub rpc_urls: Option<Vec<String>>,
+    pub keypair_path: Option<String>,
+    pub keypair_env: Option<String>,
+    pub keypair_command: Option<String>,
+    pub fee_payer_path: Option<String>,
+    pub priority_fee: Option<u64>,
+    pub lamports_per_compute_unit: Option<u64>,
+    pub priority_fee_mode: Option<PriorityFeeMode>,
+    pub priority_fee_percentile: Option<u8>,
+    pub max_priority_fee_lamports: Option<u64>,
+    pub priority_fee_cap_per_pass: Option<u64>,
+    pub dynamic_fee_url: Option<String>,
+    pub dynamic_fee_strategy: Option<String>,
+    pub ratelimit_rpc: Option<u32>,
+    pub max_retries: Option<usize>,
+    pub submit_retry_policy: Option<RetryPolicy>,
+    pub min_sol_balance: Option<f64>,
+    pub threads: Option<u64>,
+    pub buffer_time: Option<u64>,
+    pub buffer_time_adaptive: Option<bool>,
+    pub latency_window: Option<usize>,
+    pub min_difficulty: Option<u32>,
+    pub difficulty_percentile: Option<u8>,
+    pub alert_difficulty: Option<u32>,
+    pub memory_limit: Option<u64>,
+    pub max_rss_mb: Option<u64>,
+    pub max_wait_secs: Option<u64>,
+    pub max_clock_drift: Option<u64>,
+    pub max_clock_drift_action: Option<ClockDriftAction>,
+    pub on_low_difficulty: Option<LowDifficultyPolicy>,
+    pub low_difficulty_wait: Option<String>,
+    pub challenge_refresh_interval: Option<String>,
+    pub prefetch_clock: Option<bool>,
+    pub watchdog_timeout: Option<u64>,
+    pub watchdog_file: Option<String>,
+    pub pass_interval: Option<u64>,
+    pub bus_cache_ttl: Option<u64>,
+    pub proof_cache_ttl: Option<u64>,
+    pub wallets: Option<String>,
+    pub threads_per_wallet: Option<u64>,
+    pub db: Option<String>,
+    pub log_file: Option<String>,
+    pub log_rotate_size: Option<u64>,
+    pub log_rotate_keep: Option<usize>,
+    pub export_md: Option<String>,
+    pub max_temp: Option<f32>,
+    pub max_sol_per_pass: Option<f64>,
+    pub auto_claim: Option<bool>,
+    pub min_claim_ore: Option<f64>,
+    pub stake_threshold_multiplier: Option<f64>,
+    pub stake_auto: Option<bool>,
+    pub min_stake_ore: Option<f64>,
+    pub stake_before_mine: Option<bool>,
+    pub min_stake_amount: Option<f64>,
+    pub auto_topup: Option<String>,
+    pub topup_amount: Option<f64>,
+    pub on_insufficient_sol: Option<InsufficientSolPolicy>,
+    pub metrics_port: Option<u16>,
+    pub grpc_port: Option<u16>,
+    pub notify_discord: Option<String>,
+    pub notify_interval: Option<u64>,
+    pub notify_telegram: Option<String>,
+    pub notify_chat_id: Option<String>,
+    pub ore_price_feed: Option<String>,
+    pub ema_alpha: Option<f64>,
+    pub shutdown_timeout: Option<u64>,
+    pub simulate: Option<bool>,
+    pub epoch_wait: Option<bool>,
+    pub skip_register: Option<bool>,
+    pub pass_limit: Option<u64>,
+    pub max_passes_per_hour: Option<u64>,
+    pub schedule: Option<String>,
+    pub tui: Option<bool>,
+    pub quiet: Option<bool>,
+    pub no_verify: Option<bool>,
+    pub rayon: Option<bool>,
+    pub mem_prealloc: Option<bool>,
+    pub pin_threads: Option<bool>,
+    pub thread_priority: Option<ThreadPriorityLevel>,
+    pub cpu_governor: Option<CpuGovernor>,
+    pub nonce_strategy: Option<NonceStrategy>,
+    pub tpu_client: Option<bool>,
+    pub jito: Option<bool>,
+    pub jito_url: Option<String>,
+    pub jito_tip_lamports: Option<u64>,
+    pub target_hashrate: Option<u64>,
+    pub extra_instructions: Option<Vec<String>>,
+}
+
+impl MinerConfig {
+    /// Loads the config file at the given path, or at the default path if none is given.
+    /// A missing file is only an error if its path was given explicitly.
+    fn load(path: Option<&str>) -> Result<Option<FileOptions>, String> {
+        let path = match path {
+            Some(path) => PathBuf::from(path),
+            None => match default_path() {
+                Some(path) if path.exists() => path,
+                _ => return Ok(None),
+            },
+        };
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Could not read config file `{}`: {}", path.display(), err))?;
+        let config: Self = toml::from_str(&contents)
+            .map_err(|err| format!("Could not parse config file `{}`: {}", path.display(), err))?;
+        let Ok(Value::Object(values)) = serde_json::to_value(config) else {
+            return Err(format!("Could not read config file `{}`", path.display()));
+        };
+        Ok(Some(FileOptions {
+            path: path.display().to_string(),
+            values,
+        }))
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        let contents = toml::to_string_pretty(self).map_err(|err| err.to_string())?;
+        if let Some(dir) = Path::new(path).parent() {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(path, contents).map_err(|err| err.to_string())
+    }
+
+    /// Captures the effective configuration, so it can be written out for later runs.
+    pub fn from_args(args: &Args) -> Self {
+        let mut config = Self {
+            rpc_url: args.rpc.clone(),
+            network: args.network,
+            rpc_urls: Some(args.rpc_urls.clone()).filter(|urls| !urls.is_empty()),
+            keypair_path: args.keypair.clone(),
+            keypair_env: args.keypair_env.clone(),
+            keypair_command: args.keypair_command.clone(),
+            fee_payer_path: args.fee_payer.clone(),
+            priority_fee: args.priority_fee,
+            lamports_per_compute_unit: args.lamports_per_compute_unit,
+            priority_fee_mode: Some(args.priority_fee_mode),
+            priority_fee_percentile: Some(args.priority_fee_percentile),
+            max_priority_fee_lamports: args.max_priority_fee_lamports,
+            priority_fee_cap_per_pass: args.priority_fee_cap_per_pass,
+            dynamic_fee_url: args.dynamic_fee_url.clone(),
+            dynamic_fee_strategy: args.dynamic_fee_strategy.clone(),
+            ratelimit_rpc: args.ratelimit_rpc,
+            max_retries: Some(args.max_retries),
+            submit_retry_policy: Some(args.submit_retry_policy),
+            min_sol_balance: Some(args.min_sol_balance),
+            ..Default::default()
+        };
+        if let Commands::Mine(mine_args) = &args.command {
+            config.apply_mine_args(mine_args);
+        }
+        config
+    }
+
+    fn apply_mine_args(&mut self, args: &MineArgs) {
+        self.threads = Some(args.cores);
+        self.buffer_time = Some(args.buffer_time);
+        self.buffer_time_adaptive = Some(args.buffer_time_adaptive);
+        self.latency_window = Some(args.latency_window);
+        self.min_difficulty = args.min_difficulty;
+        self.difficulty_percentile = args.difficulty_percentile;
+        self.alert_difficulty = args.alert_difficulty;
+        self.memory_limit = args.memory_limit;
+        self.max_rss_mb = args.max_rss_mb;
+        self.max_wait_secs = Some(args.max_wait_secs);
+        self.max_clock_drift = args.max_clock_drift;
+        self.max_clock_drift_action = Some(args.max_clock_drift_action);
+        self.on_low_difficulty = Some(args.on_low_difficulty);
+        self.low_difficulty_wait =
+            Some(humantime::format_duration(args.low_difficulty_wait).to_string());
+        self.challenge_refresh_interval = args
+            .challenge_refresh_interval
+            .map(|interval| humantime::format_duration(interval).to_string());
+        self.prefetch_clock = Some(args.prefetch_clock);
+        self.watchdog_timeout = args.watchdog_timeout;
+        self.watchdog_file = args.watchdog_file.clone();
+        self.pass_interval = args.pass_interval;
+        self.bus_cache_ttl = Some(args.bus_cache_ttl);
+        self.proof_cache_ttl = args.proof_cache_ttl;
+        self.wallets = args.wallets.clone();
+        self.threads_per_wallet = args.threads_per_wallet;
+        self.db = args.db.clone();
+        self.log_file = args.log_file.clone();
+        self.log_rotate_size = args.log_rotate_size;
+        self.log_rotate_keep = Some(args.log_rotate_keep);
+        self.export_md = args.export_md.clone();
+        self.max_temp = args.max_temp;
+        self.max_sol_per_pass = args.max_sol_per_pass;
+        self.auto_claim = Some(args.auto_claim);
+        self.min_claim_ore = Some(args.min_claim_ore);
+        self.stake_threshold_multiplier = args.stake_threshold_multiplier;
+        self.stake_auto = Some(args.stake_auto);
+        self.min_stake_ore = Some(args.min_stake_ore);
+        self.stake_before_mine = Some(args.stake_before_mine);
+        self.min_stake_amount = Some(args.min_stake_amount);
+        self.auto_topup = args.auto_topup.clone();
+        self.topup_amount = Some(args.topup_amount);
+        self.on_insufficient_sol = Some(args.on_insufficient_sol);
+        self.metrics_port = args.metrics_port;
+        self.grpc_port = args.grpc_port;
+        self.notify_discord = args.notify_discord.clone();
+        self.notify_interval = Some(args.notify_interval);
+        self.notify_telegram = args.notify_telegram.clone();
+        self.notify_chat_id = args.notify_chat_id.clone();
+        self.ore_price_feed = args.ore_price_feed.clone();
+        self.ema_alpha = Some(args.ema_alpha);
+        self.shutdown_timeout = Some(args.shutdown_timeout);
+        self.simulate = Some(args.simulate);
+        self.epoch_wait = Some(args.epoch_wait);
+        self.skip_register = Some(args.skip_register);
+        self.pass_limit = args.pass_limit;
+        self.max_passes_per_hour = args.max_passes_per_hour;
+        self.schedule = args.schedule.clone();
+        self.tui = Some(args.tui);
+        self.quiet = Some(args.quiet);
+        self.no_verify = Some(args.no_verify);
+        self.rayon = Some(args.rayon);
+        self.mem_prealloc = Some(args.mem_prealloc);
+        self.pin_threads = Some(args.pin_threads);
+        self.thread_priority = Some(args.thread_priority);
+        self.cpu_governor = args.cpu_governor;
+        self.nonce_strategy = Some(args.nonce_strategy);
+        self.tpu_client = Some(args.tpu_client);
+        self.jito = Some(args.jito);
+        self.jito_url = Some(args.jito_url.clone());
+        self.jito_tip_lamports = Some(args.jito_tip_lamports);
+        self.target_hashrate = args.target_hashrate;
+        self.extra_instructions =
+            Some(args.extra_instruction.clone()).filter(|ixs| !ixs.is_empty());
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config").join("ore-miner").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, values: Value) -> FileOptions {
+        let Value::Object(values) = values else {
+            panic!("expected a table");
+        };
+        FileOptions {
+            path: path.to_string(),
+            values,
+        }
+    }
+
+    fn load_args(argv: &[&str], files: &[FileOptions]) -> Result<Args, String> {
+        let argv: Vec<OsString> = argv.iter().map(OsString::from).collect();
+        let matches = Args::command().get_matches_from(argv.clone());
+        match merge(argv, &matches, files)? {
+            Some(args) => Ok(args),
+            None => Ok(Args::from_arg_matches(&matches).unwrap()),
+        }
+    }
+
+    fn mine_args(args: Args) -> MineArgs {
+        let Commands::Mine(mine_args) = args.command else {
+            panic!("expected the mine command");
+        };
+        mine_args
+    }
+
+    #[test]
+    fn command_line_beats_args_file_beats_miner_config() {
+        let miner_config = file(
+            "config.toml",
+            serde_json::json!({ "threads": 2, "buffer_time": 3, "latency_window": 7 }),
+        );
+        let args_file = file(
+            "args.toml",
+            serde_json::json!({ "cores": 4, "buffer_time": 6 }),
+        );
+        let args = mine_args(
+            load_args(
+                &["ore", "mine", "--buffer-time", "9"],
+                &[miner_config, args_file],
+            )
+            .unwrap(),
+        );
+        assert_eq!(args.buffer_time, 9);
+        assert_eq!(args.cores, 4);
+        assert_eq!(args.latency_window, 7);
+    }
+
+    #[test]
+    fn global_options_apply_to_every_command() {
+        let miner_config = file(
+            "config.toml",
+            serde_json::json!({ "rpc_url": "http://localhost:8899", "threads": 2 }),
+        );
+        let args = load_args(&["ore", "balance"], &[miner_config]).unwrap();
+        assert_eq!(args.rpc.as_deref(), Some("http://localhost:8899"));
+    }
+
+    #[test]
+    fn invalid_values_name_their_key() {
+        let miner_config = file("config.toml", serde_json::json!({ "min_sol_balance": 0.0 }));
+        let err = load_args(&["ore", "mine"], &[miner_config]).unwrap_err();
+        assert!(err.starts_with("Invalid `min_sol_balance` in `config.toml`"));
+
+        let miner_config = file(
+            "config.toml",
+            serde_json::json!({ "low_difficulty_wait": "soon" }),
+        );
+        let err = load_args(&["ore", "mine"], &[miner_config]).unwrap_err();
+        assert!(err.starts_with("Invalid `low_difficulty_wait` in `config.toml`"));
+    }
+
+    #[test]
+    fn conflicting_options_are_rejected() {
+        let miner_config = file(
+            "config.toml",
+            serde_json::json!({ "auto_claim": true, "stake_auto": true }),
+        );
+        assert!(load_args(&["ore", "mine"], &[miner_config]).is_err());
+    }
+
+    #[test]
+    fn saved_defaults_do_not_trip_requires() {
+        let args_file = file(
+            "args.json",
+            serde_json::json!({ "max_clock_drift": null, "max_clock_drift_action": "warn" }),
+        );
+        assert!(load_args(&["ore", "mine"], &[args_file]).is_ok());
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        let args_file = file("args.json", serde_json::json!({ "cores_count": 4 }));
+        let err = load_args(&["ore", "mine"], &[args_file]).unwrap_err();
+        assert_eq!(err, "Unknown option `cores_count` in `args.json`");
+    }
+
+    #[test]
+    fn saved_args_load_back() {
+        let saved = mine_args(
+            load_args(
+                &[
+                    "ore",
+                    "mine",
+                    "--cores",
+                    "3",
+                    "--auto-claim",
+                    "--min-claim-ore",
+                    "2.5",
+                    "--low-difficulty-wait",
+                    "1m",
+                ],
+                &[],
+            )
+            .unwrap(),
+        );
+        let args_file = file("args.json", serde_json::to_value(&saved).unwrap());
+        let args = mine_args(load_args(&["ore", "mine"], &[args_file]).unwrap());
+        assert_eq!(args.cores, 3);
+        assert!(args.auto_claim);
+        assert_eq!(args.min_claim_ore, 2.5);
+        assert_eq!(args.low_difficulty_wait, std::time::Duration::from_secs(60));
+    }
+
+    #[test]
+    fn args_file_values_are_validated() {
+        let args_file = file("args.json", serde_json::json!({ "ema_alpha": 1.5 }));
+        let err = load_args(&["ore", "mine"], &[args_file]).unwrap_err();
+        assert!(err.starts_with("Invalid `ema_alpha` in `args.json`"));
+
+        let args_file = file("args.json", serde_json::json!({ "resume": true }));
+        assert!(load_args(&["ore", "mine"], &[args_file]).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::{args::Network, error::MineError};
+
+/// Endpoints of the well-known networks. Instructions and account addresses come from ore-api,
+/// which bakes its program id in at compile time, so every network uses that program.
+const NETWORKS: &str = include_str!("../networks.toml");
+
+#[derive(Debug, Deserialize)]
+pub struct NetworkConfig {
+    pub rpc_url: String,
+}
+
+impl Network {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Network::MainnetBeta => "mainnet-beta",
+            Network::Devnet => "devnet",
+            Network::Testnet => "testnet",
+        }
+    }
+
+    /// Looks up the network in the embedded networks table.
+    pub fn config(&self) -> Result<NetworkConfig, MineError> {
+        let mut networks: HashMap<String, NetworkConfig> =
+            toml::from_str(NETWORKS).map_err(|err| {
+                MineError::InvalidArgs(format!("Failed to parse networks.toml: {}", err))
+            })?;
+        networks.remove(self.name()).ok_or_else(|| {
+            MineError::InvalidArgs(format!("No {} entry in networks.toml", self.name()))
+        })
+    }
+}
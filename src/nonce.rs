@@ -0,0 +1,163 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use rand::Rng;
+
+use crate::args::NonceStrategy;
+
+/// Number of nonces a thread takes at a time.
+pub const NONCE_BATCH: u64 = 256;
+
+/// `NONCE_BATCH` nonces, `step` apart from `start`.
+pub struct NonceBatch {
+    start: u64,
+    step: u64,
+}
+
+impl NonceBatch {
+    /// Returns the nonce at the index within the batch.
+    pub fn nonce(&self, index: u64) -> u64 {
+        self.start.wrapping_add(index.wrapping_mul(self.step))
+    }
+}
+
+/// Hands a hashing thread the nonces to try, one batch at a time.
+pub trait NonceIterator {
+    fn next_batch(&mut self) -> NonceBatch;
+}
+
+/// Claims consecutive batches from a counter shared by every thread, so faster threads do more
+/// of the work.
+struct Linear {
+    next: Arc<AtomicU64>,
+}
+
+impl NonceIterator for Linear {
+    fn next_batch(&mut self) -> NonceBatch {
+        NonceBatch {
+            start: self.next.fetch_add(NONCE_BATCH, Ordering::Relaxed),
+            step: 1,
+        }
+    }
+}
+
+/// Interleaves the threads, with each one taking every `threads`th nonce.
+struct Strided {
+    next: u64,
+    threads: u64,
+}
+
+impl NonceIterator for Strided {
+    fn next_batch(&mut self) -> NonceBatch {
+        let batch = NonceBatch {
+            start: self.next,
+            step: self.threads,
+        };
+        self.next = self
+            .next
+            .wrapping_add(NONCE_BATCH.wrapping_mul(self.threads));
+        batch
+    }
+}
+
+/// Hashes upward from a random nonce within the thread's own slice of the nonce space,
+/// wrapping around to the start of the slice.
+struct Random {
+    base: u64,
+    span: u64,
+    offset: u64,
+}
+
+impl NonceIterator for Random {
+    fn next_batch(&mut self) -> NonceBatch {
+        let batch = NonceBatch {
+            start: self.base.wrapping_add(self.offset),
+            step: 1,
+        };
+        self.offset = self.offset.wrapping_add(NONCE_BATCH) % self.span;
+        batch
+    }
+}
+
+/// Returns the nonces for thread `index` of `threads`, searching from `start`. Linear threads
+/// share the `next` counter, which begins at `start`.
+pub fn nonce_iterator(
+    strategy: NonceStrategy,
+    index: u64,
+    threads: u64,
+    start: u64,
+    next: &Arc<AtomicU64>,
+) -> Box<dyn NonceIterator> {
+    let threads = threads.max(1);
+    match strategy {
+        NonceStrategy::Linear => Box::new(Linear { next: next.clone() }),
+        NonceStrategy::Strided => Box::new(Strided {
+            next: start.wrapping_add(index),
+            threads,
+        }),
+        NonceStrategy::Random => {
+            let span = (u64::MAX - start).saturating_div(threads).max(1);
+            Box::new(Random {
+                base: start.wrapping_add(span.wrapping_mul(index)),
+                span,
+                offset: rand::thread_rng().gen_range(0..span),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn batch_nonces(iterator: &mut dyn NonceIterator) -> Vec<u64> {
+        let batch = iterator.next_batch();
+        (0..NONCE_BATCH).map(|index| batch.nonce(index)).collect()
+    }
+
+    #[test]
+    fn linear_threads_claim_consecutive_batches() {
+        let next = Arc::new(AtomicU64::new(1_000));
+        let mut first = nonce_iterator(NonceStrategy::Linear, 0, 2, 1_000, &next);
+        let mut second = nonce_iterator(NonceStrategy::Linear, 1, 2, 1_000, &next);
+        assert_eq!(batch_nonces(first.as_mut())[0], 1_000);
+        assert_eq!(batch_nonces(second.as_mut())[0], 1_000 + NONCE_BATCH);
+        assert_eq!(batch_nonces(first.as_mut())[0], 1_000 + 2 * NONCE_BATCH);
+    }
+
+    #[test]
+    fn strided_threads_cover_every_nonce_once() {
+        let next = Arc::new(AtomicU64::new(0));
+        let threads = 4;
+        let mut nonces = HashSet::new();
+        for index in 0..threads {
+            let mut iterator = nonce_iterator(NonceStrategy::Strided, index, threads, 10, &next);
+            for _ in 0..2 {
+                for nonce in batch_nonces(iterator.as_mut()) {
+                    assert!(nonces.insert(nonce), "nonce {} hashed twice", nonce);
+                }
+            }
+        }
+        let expected: HashSet<u64> = (10..10 + 2 * NONCE_BATCH * threads).collect();
+        assert_eq!(nonces, expected);
+    }
+
+    #[test]
+    fn random_threads_stay_in_their_slice() {
+        let next = Arc::new(AtomicU64::new(0));
+        let threads = 4;
+        let span = u64::MAX / threads;
+        for index in 0..threads {
+            let mut iterator = nonce_iterator(NonceStrategy::Random, index, threads, 0, &next);
+            for _ in 0..4 {
+                let batch = iterator.next_batch();
+                let first = batch.nonce(0);
+                assert!(first >= span * index && first < span * (index + 1));
+            }
+        }
+    }
+}
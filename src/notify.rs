@@ -0,0 +1,210 @@
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use reqwest::{Client, StatusCode};
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use crate::{args::OutputFormat, output::print_warning};
+
+/// Discord allows 5 webhook requests per second.
+const RATE_LIMIT: usize = 5;
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+const SEND_ATTEMPTS: usize = 3;
+
+/// Time to wait for a message sent before the miner exits.
+const TELEGRAM_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct DiscordNotifier {
+    url: String,
+    client: Client,
+    output: OutputFormat,
+    sent: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+pub struct DiscordSummary {
+    pub wallet: String,
+    pub passes: u64,
+    pub session_ore: f64,
+    pub session_sol: f64,
+    pub difficulty: u32,
+    pub sol_balance: f64,
+    pub staked_balance: f64,
+    pub uptime_secs: u64,
+}
+
+impl DiscordNotifier {
+    pub fn new(url: String, output: OutputFormat) -> Self {
+        Self {
+            url,
+            client: Client::new(),
+            output,
+            sent: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Posts the summary in the background, so the mining loop is never held up.
+    pub fn notify(&self, summary: DiscordSummary) {
+        let url = self.url.clone();
+        let client = self.client.clone();
+        let output = self.output;
+        let sent = self.sent.clone();
+        tokio::spawn(async move {
+            let body = summary.embed();
+            for _ in 0..SEND_ATTEMPTS {
+                wait_for_slot(&sent).await;
+                match client.post(&url).json(&body).send().await {
+                    Ok(response) if response.status().eq(&StatusCode::TOO_MANY_REQUESTS) => {
+                        let retry_after = response
+                            .json::<Value>()
+                            .await
+                            .ok()
+                            .and_then(|body| body["retry_after"].as_f64())
+                            .unwrap_or(1.0);
+                        tokio::time::sleep(Duration::from_secs_f64(retry_after)).await;
+                    }
+                    Ok(response) if !response.status().is_success() => {
+                        print_warning(
+                            output,
+                            &format!("Discord notification failed: {}", response.status()),
+                        );
+                        return;
+                    }
+                    Ok(_) => return,
+                    Err(err) => {
+                        print_warning(output, &format!("Discord notification failed: {}", err));
+                        return;
+                    }
+                }
+            }
+            print_warning(output, "Discord notification failed: rate limited");
+        });
+    }
+}
+
+/// Posts plain text messages to a Telegram chat through the Bot API.
+pub struct TelegramNotifier {
+    url: String,
+    chat_id: String,
+    client: Client,
+    output: OutputFormat,
+}
+
+impl TelegramNotifier {
+    pub fn new(bot_token: String, chat_id: String, output: OutputFormat) -> Self {
+        Self {
+            url: format!("https://api.telegram.org/bot{}/sendMessage", bot_token),
+            chat_id,
+            client: Client::new(),
+            output,
+        }
+    }
+
+    /// Sends the message in the background, so the mining loop is never held up.
+    pub fn notify(&self, text: String) {
+        let url = self.url.clone();
+        let chat_id = self.chat_id.clone();
+        let client = self.client.clone();
+        let output = self.output;
+        tokio::spawn(async move {
+            send_telegram(&client, &url, &chat_id, &text, output).await;
+        });
+    }
+
+    /// Sends the message and waits for it, for events after which the miner exits.
+    pub async fn notify_now(&self, text: String) {
+        let send = send_telegram(&self.client, &self.url, &self.chat_id, &text, self.output);
+        if tokio::time::timeout(TELEGRAM_TIMEOUT, send).await.is_err() {
+            print_warning(self.output, "Telegram notification failed: timed out");
+        }
+    }
+}
+
+async fn send_telegram(
+    client: &Client,
+    url: &str,
+    chat_id: &str,
+    text: &str,
+    output: OutputFormat,
+) {
+    let body = json!({
+        "chat_id": chat_id,
+        "text": text,
+    });
+    match client.post(url).json(&body).send().await {
+        Ok(response) if !response.status().is_success() => {
+            print_warning(
+                output,
+                &format!("Telegram notification failed: {}", response.status()),
+            );
+        }
+        Ok(_) => {}
+        Err(err) => {
+            // Hide the bot token, which is part of the url
+            print_warning(
+                output,
+                &format!("Telegram notification failed: {}", err.without_url()),
+            );
+        }
+    }
+}
+
+impl DiscordSummary {
+    fn daily_ore_rate(&self) -> f64 {
+        if self.uptime_secs.eq(&0) {
+            return 0.0;
+        }
+        self.session_ore / self.uptime_secs as f64 * 86_400.0
+    }
+
+    fn embed(&self) -> Value {
+        json!({
+            "embeds": [{
+                "title": "ORE mining summary",
+                "description": format!("{} passes by {}", self.passes, self.wallet),
+                "fields": [
+                    field("Session ORE", format!("{:.11}", self.session_ore)),
+                    field("Session SOL", format!("{:.9}", self.session_sol)),
+                    field("Difficulty", self.difficulty.to_string()),
+                    field("SOL balance", format!("{:.9}", self.sol_balance)),
+                    field("Staked ORE", format!("{:.11}", self.staked_balance)),
+                    field("Uptime", format_uptime(self.uptime_secs)),
+                    field("Est. ORE/day", format!("{:.4}", self.daily_ore_rate())),
+                ],
+                "timestamp": chrono::Utc::now().to_rfc3339(),
+            }]
+        })
+    }
+}
+
+fn field(name: &str, value: String) -> Value {
+    json!({ "name": name, "value": value, "inline": true })
+}
+
+fn format_uptime(secs: u64) -> String {
+    format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+}
+
+/// Waits until another request can be sent without exceeding the rate limit.
+async fn wait_for_slot(sent: &Mutex<VecDeque<Instant>>) {
+    loop {
+        let wait = {
+            let mut sent = sent.lock().await;
+            while sent
+                .front()
+                .map_or(false, |at| at.elapsed().ge(&RATE_WINDOW))
+            {
+                sent.pop_front();
+            }
+            if sent.len().lt(&RATE_LIMIT) {
+                sent.push_back(Instant::now());
+                return;
+            }
+            RATE_WINDOW.saturating_sub(sent.front().unwrap().elapsed())
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
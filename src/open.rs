@@ -1,22 +1,109 @@
+use colored::*;
 use solana_sdk::signature::Signer;
+use tracing::{info, warn};
 
-use crate::{send_and_confirm::ComputeBudget, utils::proof_pubkey, Miner};
+use crate::{
+    cu_limits::CU_LIMIT_MAX,
+    error::MineError,
+    utils::{amount_u64_to_string, proof_pubkey, try_get_proof_with_authority},
+    Miner,
+};
 
 impl Miner {
-    pub async fn open(&self) {
+    /// Opens the signer's proof account if it does not exist yet. With force, closes an existing
+    /// proof first, which requires its stake to be empty.
+    pub async fn open(
+        &self,
+        signer: &dyn Signer,
+        fee_payer: &dyn Signer,
+        force: bool,
+    ) -> Result<(), MineError> {
         // Return early if miner is already registered
-        let signer = self.signer();
-        let fee_payer = self.fee_payer();
         let proof_address = proof_pubkey(signer.pubkey());
         if self.rpc_client.get_account(&proof_address).await.is_ok() {
-            return;
+            if !force {
+                return Ok(());
+            }
+            self.close_for_reopen(signer, fee_payer).await?;
         }
 
         // Sign and send transaction.
-        println!("Generating challenge...");
+        info!("Generating challenge...");
         let ix = ore_api::instruction::open(signer.pubkey(), signer.pubkey(), fee_payer.pubkey());
-        self.send_and_confirm(&[ix], ComputeBudget::Dynamic, false)
-            .await
-            .ok();
+        self.send_and_confirm_with_signer(
+            signer,
+            fee_payer,
+            &[ix],
+            self.compute_budget(CU_LIMIT_MAX),
+            false,
+            self.retry_policy,
+        )
+        .await
+        .ok();
+        Ok(())
+    }
+
+    /// Closes the signer's proof so it can be opened again, failing if it still holds stake.
+    async fn close_for_reopen(
+        &self,
+        signer: &dyn Signer,
+        fee_payer: &dyn Signer,
+    ) -> Result<(), MineError> {
+        let proof = try_get_proof_with_authority(&self.rpc_client, signer.pubkey()).await;
+        if let Some(proof) = proof.filter(|proof| proof.balance.gt(&0)) {
+            return Err(MineError::InvalidArgs(format!(
+                "Cannot re-register {} with {} ORE staked. Claim your stake first.",
+                signer.pubkey(),
+                amount_u64_to_string(proof.balance)
+            )));
+        }
+        warn!("Re-registering {}", signer.pubkey());
+        let ix = ore_api::instruction::close(signer.pubkey());
+        self.send_and_confirm_with_signer(
+            signer,
+            fee_payer,
+            &[ix],
+            self.compute_budget(CU_LIMIT_MAX),
+            false,
+            self.retry_policy,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn register(&self) -> Result<(), MineError> {
+        let signer = self.try_signer()?;
+        let fee_payer = self.try_fee_payer()?;
+        let proof_address = proof_pubkey(signer.pubkey());
+
+        // Print the proof if the miner is already registered
+        if let Some(proof) = try_get_proof_with_authority(&self.rpc_client, signer.pubkey()).await {
+            info!("Already registered: {}", proof_address);
+            info!("Balance: {} ORE", amount_u64_to_string(proof.balance));
+            info!(
+                "Last hash: {}",
+                solana_sdk::hash::Hash::new_from_array(proof.last_hash)
+            );
+            info!(
+                "Challenge: {}",
+                solana_sdk::hash::Hash::new_from_array(proof.challenge)
+            );
+            return Ok(());
+        }
+
+        // Sign and send transaction.
+        info!("Registering {}...", proof_address);
+        let ix = ore_api::instruction::open(signer.pubkey(), signer.pubkey(), fee_payer.pubkey());
+        self.send_and_confirm_with_signer(
+            signer.as_ref(),
+            fee_payer.as_ref(),
+            &[ix],
+            self.compute_budget(CU_LIMIT_MAX),
+            false,
+            self.retry_policy,
+        )
+        .await?;
+        info!("{} Registered {}", "OK".bold().green(), proof_address);
+        Ok(())
     }
 }
@@ -0,0 +1,120 @@
+use std::{
+    future::Future,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
+
+use colored::*;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::{args::OutputFormat, tui, Miner};
+
+impl Miner {
+    /// Returns whether output is printed as events, either as JSON lines or to the dashboard.
+    pub fn is_json(&self) -> bool {
+        self.output.eq(&OutputFormat::Json) || tui::is_active()
+    }
+
+    pub fn print_warning(&self, message: &str) {
+        print_warning(self.output, message);
+    }
+}
+
+/// Prints a warning in the given format.
+pub fn print_warning(output: OutputFormat, message: &str) {
+    if output.eq(&OutputFormat::Json) || tui::is_active() {
+        print_json_event("warning", json!({ "message": message }));
+    } else {
+        warn!("{}", message);
+    }
+}
+
+/// Whether JSON events are indented across lines instead of printed one per line.
+static JSON_PRETTY: AtomicBool = AtomicBool::new(false);
+
+/// Id of the mining session, added to every event once it starts.
+static SESSION_ID: OnceLock<String> = OnceLock::new();
+
+tokio::task_local! {
+    /// Wallet whose passes are being mined, added to the events printed while mining them.
+    static WALLET: String;
+}
+
+pub fn set_session_id(session_id: &str) {
+    SESSION_ID.set(session_id.to_string()).ok();
+}
+
+pub fn session_id() -> Option<&'static str> {
+    SESSION_ID.get().map(String::as_str)
+}
+
+/// Runs the future with its events tagged with the wallet.
+pub async fn with_wallet<F: Future>(wallet: String, future: F) -> F::Output {
+    WALLET.scope(wallet, future).await
+}
+
+pub fn set_json_pretty(pretty: bool) {
+    JSON_PRETTY.store(pretty, Ordering::Relaxed);
+}
+
+/// Prints a single event as a JSON line on stdout, or feeds it to the dashboard if it is open.
+pub fn print_json_event(event: &str, mut fields: Value) {
+    fields["event"] = Value::from(event);
+    fields["timestamp_utc"] = Value::from(chrono::Utc::now().to_rfc3339());
+    if let Some(session_id) = session_id() {
+        fields["session_id"] = Value::from(session_id);
+    }
+    if let Ok(wallet) = WALLET.try_with(|wallet| wallet.clone()) {
+        fields["wallet_pubkey"] = Value::from(wallet);
+    }
+    if tui::is_active() {
+        tui::record_event(event, &fields);
+        return;
+    }
+    // Bypass the log subscriber, so events are neither filtered by level nor wrapped again
+    let mut stdout = std::io::stdout().lock();
+    if JSON_PRETTY.load(Ordering::Relaxed) {
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&fields).unwrap()).ok();
+    } else {
+        writeln!(stdout, "{}", fields).ok();
+    }
+}
+
+/// Rings the terminal bell and prints a banner across the terminal for a new difficulty record.
+/// Prints nothing in JSON mode or on the dashboard, where the pass event flags the record.
+pub fn print_record_banner(output: OutputFormat, difficulty: u32, hash: &str) {
+    if output.eq(&OutputFormat::Json) || tui::is_active() {
+        return;
+    }
+    let width = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(BANNER_WIDTH);
+    let rule = "=".repeat(width);
+    let lines = [
+        format!("NEW RECORD DIFFICULTY: {}", difficulty),
+        format!("Hash: {}", hash),
+        format!("Time: {}", chrono::Utc::now().to_rfc3339()),
+    ];
+
+    // Only ring the bell where colors are on, which is a terminal without --no-color
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        print!("\x07");
+    }
+    info!("{}", rule.bright_green().bold());
+    for line in lines {
+        info!(
+            "{}",
+            format!("{:^width$}", line, width = width)
+                .bright_green()
+                .bold()
+        );
+    }
+    info!("{}", rule.bright_green().bold());
+}
+
+const BANNER_WIDTH: usize = 80;
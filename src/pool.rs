@@ -0,0 +1,179 @@
+#[cfg(feature = "cli")]
+use colored::*;
+#[cfg(feature = "cli")]
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "cli")]
+use serde_json::json;
+#[cfg(feature = "cli")]
+use solana_sdk::signature::Signer;
+#[cfg(feature = "cli")]
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+};
+#[cfg(feature = "cli")]
+use tracing::{error, info};
+
+#[cfg(feature = "cli")]
+use crate::{args::PoolArgs, error::MineError, output::print_json_event, Miner};
+
+/// Messages exchanged with the pool server over the websocket, tagged by `type`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PoolMessage {
+    Challenge {
+        challenge: String,
+        cutoff: u64,
+        min_difficulty: u32,
+        // Slice of the nonce space assigned to this client
+        #[serde(default)]
+        nonce_start: u64,
+        #[serde(default = "nonce_end_default")]
+        nonce_end: u64,
+    },
+    Share {
+        wallet: String,
+        nonce: u64,
+        difficulty: u32,
+        digest: String,
+        hash: String,
+    },
+    Accepted {
+        difficulty: u32,
+    },
+    Rejected {
+        reason: String,
+    },
+    Stats {
+        hashrate: f64,
+        estimated_earnings: f64,
+    },
+}
+
+#[cfg(feature = "cli")]
+impl Miner {
+    pub async fn pool(&self, args: PoolArgs) -> Result<(), MineError> {
+        // Check num threads
+        self.check_num_cores(args.cores);
+
+        // Connect to pool
+        let mut request = args
+            .pool_url
+            .as_str()
+            .into_client_request()
+            .map_err(|err| MineError::InvalidArgs(format!("Invalid pool url: {}", err)))?;
+        if let Some(api_key) = &args.pool_api_key {
+            let value = HeaderValue::from_str(&format!("Bearer {}", api_key))
+                .map_err(|err| MineError::InvalidArgs(format!("Invalid pool api key: {}", err)))?;
+            request.headers_mut().insert("Authorization", value);
+        }
+        let wallet = self.try_signer()?.pubkey().to_string();
+        let (stream, _) = connect_async(request)
+            .await
+            .map_err(|err| MineError::Pool(format!("Failed to connect to pool: {}", err)))?;
+        if !self.is_json() {
+            info!("Connected to pool {}", args.pool_url);
+        }
+        let (mut write, mut read) = stream.split();
+
+        // Mine challenges until the pool disconnects
+        while let Some(message) = read.next().await {
+            let text = match message {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Close(_)) => break,
+                Ok(_) => continue,
+                Err(err) => {
+                    return Err(MineError::Pool(format!("Pool connection error: {}", err)));
+                }
+            };
+            let Ok(message) = serde_json::from_str::<PoolMessage>(&text) else {
+                self.print_warning(&format!("Unrecognized pool message: {}", text));
+                continue;
+            };
+            match message {
+                PoolMessage::Challenge {
+                    challenge,
+                    cutoff,
+                    min_difficulty,
+                    nonce_start,
+                    nonce_end,
+                } => {
+                    let Some(challenge) = parse_challenge(&challenge) else {
+                        self.print_warning(&format!("Invalid challenge: {}", challenge));
+                        continue;
+                    };
+                    let best_hash = Self::find_hash_par_from(
+                        challenge,
+                        cutoff,
+                        args.cores,
+                        min_difficulty,
+                        nonce_start..nonce_end,
+                    )
+                    .await;
+                    let share = PoolMessage::Share {
+                        wallet: wallet.clone(),
+                        nonce: u64::from_le_bytes(best_hash.solution.n),
+                        difficulty: best_hash.difficulty,
+                        digest: bs58::encode(best_hash.solution.d).into_string(),
+                        hash: bs58::encode(best_hash.hash.h).into_string(),
+                    };
+                    let share = serde_json::to_string(&share).map_err(|err| {
+                        MineError::Pool(format!("Failed to encode share: {}", err))
+                    })?;
+                    write.send(Message::Text(share)).await.map_err(|err| {
+                        MineError::Pool(format!("Failed to submit share: {}", err))
+                    })?;
+                }
+                PoolMessage::Accepted { difficulty } => {
+                    if self.is_json() {
+                        print_json_event("share_accepted", json!({ "difficulty": difficulty }));
+                    } else {
+                        info!(
+                            "{} Share accepted (difficulty: {})",
+                            "OK".bold().green(),
+                            difficulty
+                        );
+                    }
+                }
+                PoolMessage::Rejected { reason } => {
+                    if self.is_json() {
+                        print_json_event("share_rejected", json!({ "reason": reason }));
+                    } else {
+                        error!("Share rejected: {}", reason);
+                    }
+                }
+                PoolMessage::Stats {
+                    hashrate,
+                    estimated_earnings,
+                } => {
+                    if self.is_json() {
+                        print_json_event(
+                            "pool_stats",
+                            json!({
+                                "hashrate": hashrate,
+                                "estimated_earnings": estimated_earnings,
+                            }),
+                        );
+                    } else {
+                        info!(
+                            "Pool hashrate: {:.0} H/sec\n  Estimated earnings: {} ORE",
+                            hashrate, estimated_earnings
+                        );
+                    }
+                }
+                PoolMessage::Share { .. } => {}
+            }
+        }
+        info!("Disconnected from pool");
+        Ok(())
+    }
+}
+
+fn nonce_end_default() -> u64 {
+    u64::MAX
+}
+
+pub fn parse_challenge(challenge: &str) -> Option<[u8; 32]> {
+    bs58::decode(challenge).into_vec().ok()?.try_into().ok()
+}
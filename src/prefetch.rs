@@ -0,0 +1,64 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ore_api::state::Config;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::clock::Clock;
+use tokio::task::JoinHandle;
+
+use crate::{
+    error::MineError,
+    utils::{get_clock, get_config},
+    Miner,
+};
+
+/// Age past which prefetched accounts are fetched again.
+const MAX_PREFETCH_AGE: Duration = Duration::from_secs(5);
+
+/// Clock and config reads started at the end of a pass, for the next pass to pick up.
+pub struct Prefetch {
+    fetched_at: Instant,
+    clock: JoinHandle<Result<Clock, MineError>>,
+    config: JoinHandle<Result<Config, MineError>>,
+}
+
+impl Prefetch {
+    pub fn start(rpc_client: Arc<RpcClient>) -> Self {
+        Self {
+            fetched_at: Instant::now(),
+            clock: tokio::spawn({
+                let rpc_client = rpc_client.clone();
+                async move { get_clock(&rpc_client).await }
+            }),
+            config: tokio::spawn(async move { get_config(&rpc_client).await }),
+        }
+    }
+}
+
+impl Miner {
+    /// Returns the prefetched clock and config if they are recent enough, or fetches them now.
+    /// The prefetched clock is advanced by the time since it was read.
+    pub async fn clock_and_config(
+        &self,
+        prefetch: Option<Prefetch>,
+    ) -> Result<(Clock, Config), MineError> {
+        if let Some(prefetch) = prefetch {
+            let age = prefetch.fetched_at.elapsed();
+            if age.le(&MAX_PREFETCH_AGE) {
+                if let (Ok(Ok(mut clock)), Ok(Ok(config))) =
+                    (prefetch.clock.await, prefetch.config.await)
+                {
+                    clock.unix_timestamp =
+                        clock.unix_timestamp.saturating_add(age.as_secs() as i64);
+                    return Ok((clock, config));
+                }
+            } else {
+                prefetch.clock.abort();
+                prefetch.config.abort();
+            }
+        }
+        Ok((self.get_clock().await?, get_config(&self.rpc_client).await?))
+    }
+}
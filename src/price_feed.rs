@@ -0,0 +1,162 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+
+use crate::{args::OutputFormat, output::print_warning};
+
+/// Time between price fetches.
+const PRICE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Magic number at the start of every Pyth price account.
+const PYTH_MAGIC: u32 = 0xa1b2c3d4;
+
+/// Byte offsets of the exponent and aggregate price in a Pyth price account.
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_PRICE_OFFSET: usize = 208;
+
+/// A source of the ORE price in USD.
+#[async_trait]
+pub trait PriceFeed: Send + Sync {
+    async fn fetch_price(&self) -> Result<f64, String>;
+}
+
+/// Reads the price from a CoinGecko simple price url, such as
+/// `https://api.coingecko.com/api/v3/simple/price?ids=ore&vs_currencies=usd`.
+pub struct CoinGeckoPriceFeed {
+    url: String,
+    client: Client,
+}
+
+/// Reads the aggregate price from a Pyth price account.
+pub struct PythPriceFeed {
+    address: Pubkey,
+    rpc_client: Arc<RpcClient>,
+}
+
+/// The latest price, refreshed in the background.
+pub struct OrePrice {
+    price: Arc<Mutex<Option<f64>>>,
+}
+
+impl CoinGeckoPriceFeed {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for CoinGeckoPriceFeed {
+    async fn fetch_price(&self) -> Result<f64, String> {
+        let response: Value = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|err| err.to_string())?
+            .json()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        // The response is keyed by coin id, then by currency
+        response
+            .as_object()
+            .and_then(|coins| coins.values().next())
+            .and_then(|coin| coin["usd"].as_f64())
+            .ok_or_else(|| format!("Unexpected response: {}", response))
+    }
+}
+
+impl PythPriceFeed {
+    pub fn new(address: Pubkey, rpc_client: Arc<RpcClient>) -> Self {
+        Self {
+            address,
+            rpc_client,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for PythPriceFeed {
+    async fn fetch_price(&self) -> Result<f64, String> {
+        let data = self
+            .rpc_client
+            .get_account_data(&self.address)
+            .await
+            .map_err(|err| err.to_string())?;
+        if data.len().lt(&(PYTH_PRICE_OFFSET + 8))
+            || u32::from_le_bytes(data[0..4].try_into().unwrap()).ne(&PYTH_MAGIC)
+        {
+            return Err(format!("{} is not a Pyth price account", self.address));
+        }
+        let expo = i32::from_le_bytes(
+            data[PYTH_EXPO_OFFSET..PYTH_EXPO_OFFSET + 4]
+                .try_into()
+                .unwrap(),
+        );
+        let price = i64::from_le_bytes(
+            data[PYTH_PRICE_OFFSET..PYTH_PRICE_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        );
+        Ok(price as f64 * 10f64.powi(expo))
+    }
+}
+
+impl OrePrice {
+    /// Fetches the price now and every ten minutes after, without blocking the caller.
+    pub fn start(feed: Box<dyn PriceFeed>, output: OutputFormat) -> Self {
+        let price = Arc::new(Mutex::new(None));
+        tokio::spawn({
+            let price = price.clone();
+            async move {
+                let mut interval = tokio::time::interval(PRICE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let latest = match feed.fetch_price().await {
+                        Ok(latest) => Some(latest),
+                        Err(err) => {
+                            print_warning(output, &format!("Failed to fetch ORE price: {}", err));
+                            None
+                        }
+                    };
+                    *price.lock().unwrap() = latest;
+                }
+            }
+        });
+        Self { price }
+    }
+
+    /// Returns the latest price in USD, if the last fetch succeeded.
+    pub fn usd(&self) -> Option<f64> {
+        *self.price.lock().unwrap()
+    }
+}
+
+/// Picks the feed for a url, or for the address of a Pyth price account.
+pub fn price_feed(source: &str, rpc_client: Arc<RpcClient>) -> Result<Box<dyn PriceFeed>, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return Ok(Box::new(CoinGeckoPriceFeed::new(source.to_string())));
+    }
+    Pubkey::from_str(source)
+        .map(|address| Box::new(PythPriceFeed::new(address, rpc_client)) as Box<dyn PriceFeed>)
+        .map_err(|_| format!("{} is neither a url nor a Pyth price account", source))
+}
+
+/// Formats estimated hourly earnings, or `$?.??` if the price is unknown.
+pub fn format_usd_per_hour(usd_per_hour: Option<f64>) -> String {
+    match usd_per_hour {
+        Some(usd_per_hour) => format!("≈ ${:.3}/hr", usd_per_hour),
+        None => "≈ $?.??/hr".to_string(),
+    }
+}
@@ -4,39 +4,42 @@ use ore_api::consts::TOKEN_DECIMALS;
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signature::Signer;
 use spl_token::amount_to_ui_amount;
+use tracing::info;
 
 use crate::{
     args::ProofArgs,
+    error::MineError,
     utils::{get_proof, proof_pubkey},
     Miner,
 };
 
 impl Miner {
-    pub async fn proof(&self, args: ProofArgs) {
-        let signer = self.signer();
+    pub async fn proof(&self, args: ProofArgs) -> Result<(), MineError> {
         let address = if let Some(address) = args.address {
-            Pubkey::from_str(&address).unwrap()
+            Pubkey::from_str(&address)
+                .map_err(|_| MineError::InvalidArgs(format!("Invalid address: {}", address)))?
         } else {
-            proof_pubkey(signer.pubkey())
+            proof_pubkey(self.try_signer()?.pubkey())
         };
-        let proof = get_proof(&self.rpc_client, address).await;
-        println!("Address: {:?}", address);
-        println!("Authority: {:?}", proof.authority);
-        println!(
+        let proof = get_proof(&self.rpc_client, address).await?;
+        info!("Address: {:?}", address);
+        info!("Authority: {:?}", proof.authority);
+        info!(
             "Balance: {:?} ORE",
             amount_to_ui_amount(proof.balance, TOKEN_DECIMALS)
         );
-        println!(
+        info!(
             "Last hash: {}",
             solana_sdk::hash::Hash::new_from_array(proof.last_hash).to_string()
         );
-        println!("Last hash at: {:?}", proof.last_hash_at);
-        println!("Last stake at: {:?}", proof.last_stake_at);
-        println!("Miner: {:?}", proof.miner);
-        println!("Total hashes: {:?}", proof.total_hashes);
-        println!(
+        info!("Last hash at: {:?}", proof.last_hash_at);
+        info!("Last stake at: {:?}", proof.last_stake_at);
+        info!("Miner: {:?}", proof.miner);
+        info!("Total hashes: {:?}", proof.total_hashes);
+        info!(
             "Total rewards: {:?} ORE",
             amount_to_ui_amount(proof.total_rewards, TOKEN_DECIMALS)
         );
+        Ok(())
     }
 }
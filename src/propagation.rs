@@ -0,0 +1,66 @@
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use solana_program::clock::Clock;
+
+use crate::{error::MineError, utils::get_clock, Miner};
+
+/// Number of clock samples averaged into the propagation estimate.
+const PROPAGATION_SAMPLES: usize = 10;
+
+/// Number of clock reads that seed the estimate before the first pass.
+const SEED_SAMPLES: usize = 3;
+
+/// A rolling estimate of how far the cluster clock seen through the rpc lags behind local time.
+#[derive(Default)]
+pub struct PropagationEstimate {
+    samples: VecDeque<u64>,
+}
+
+impl PropagationEstimate {
+    /// Records the lag of a clock read just now.
+    pub fn record(&mut self, clock: &Clock) {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis() as i64)
+            .unwrap_or_default();
+        let lag_ms = now_ms
+            .saturating_sub(clock.unix_timestamp.saturating_mul(1000))
+            .max(0) as u64;
+        self.samples.push_back(lag_ms);
+        while self.samples.len() > PROPAGATION_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Returns the average lag of recent samples in milliseconds.
+    pub fn latency_ms(&self) -> u64 {
+        if self.samples.is_empty() {
+            return 0;
+        }
+        self.samples.iter().sum::<u64>() / self.samples.len() as u64
+    }
+}
+
+impl Miner {
+    /// Reads the clock, updating the propagation estimate.
+    pub async fn get_clock(&self) -> Result<Clock, MineError> {
+        let clock = get_clock(&self.rpc_client).await?;
+        self.propagation.lock().unwrap().record(&clock);
+        Ok(clock)
+    }
+
+    /// Reads the clock a few times in a row, so the first pass has an estimate to work with.
+    pub async fn seed_propagation_latency(&self) {
+        for _ in 0..SEED_SAMPLES {
+            self.get_clock().await.ok();
+        }
+    }
+
+    /// Returns the estimated propagation latency in milliseconds.
+    pub fn propagation_latency_ms(&self) -> u64 {
+        self.propagation.lock().unwrap().latency_ms()
+    }
+}
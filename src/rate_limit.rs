@@ -0,0 +1,152 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use solana_client::{client_error::Result as ClientResult, rpc_request::RpcRequest};
+use solana_rpc_client::rpc_sender::{RpcSender, RpcTransportStats};
+
+use crate::{args::OutputFormat, output::print_warning};
+
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refilled_at: Instant,
+    throttled: u64,
+    logged_at: Option<Instant>,
+}
+
+impl TokenBucket {
+    /// Refills the bucket for the time since the last call and reserves a token, returning how
+    /// long to wait out any deficit.
+    fn take(&mut self, now: Instant, calls_per_second: f64) -> Option<Duration> {
+        let refill = now.duration_since(self.refilled_at).as_secs_f64() * calls_per_second;
+        self.tokens = (self.tokens + refill).min(self.capacity);
+        self.refilled_at = now;
+        self.tokens -= 1.0;
+        if self.tokens.ge(&0.0) {
+            return None;
+        }
+        Some(Duration::from_secs_f64(-self.tokens / calls_per_second))
+    }
+}
+
+/// An rpc sender that paces requests through a token bucket, so they stay within the
+/// rate limit of the node. Bursts of up to one second's worth of calls are let through.
+pub struct RateLimitedRpcClient<S> {
+    sender: S,
+    calls_per_second: f64,
+    bucket: Mutex<TokenBucket>,
+    output: OutputFormat,
+}
+
+impl<S> RateLimitedRpcClient<S> {
+    pub fn new(sender: S, calls_per_second: u32, output: OutputFormat) -> Self {
+        let calls_per_second = calls_per_second.max(1) as f64;
+        Self {
+            sender,
+            calls_per_second,
+            bucket: Mutex::new(TokenBucket {
+                tokens: calls_per_second,
+                capacity: calls_per_second,
+                refilled_at: Instant::now(),
+                throttled: 0,
+                logged_at: None,
+            }),
+            output,
+        }
+    }
+
+    /// Takes a token, sleeping until one is available if the bucket is empty.
+    async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let Some(wait) = bucket.take(now, self.calls_per_second) else {
+                return;
+            };
+
+            // Log throttling at most once per interval
+            bucket.throttled += 1;
+            if bucket.logged_at.map_or(true, |logged_at| {
+                now.duration_since(logged_at).ge(&LOG_INTERVAL)
+            }) {
+                print_warning(
+                    self.output,
+                    &format!(
+                        "[RATE LIMITED] Delayed {} rpc calls to stay under {} calls/sec",
+                        bucket.throttled, self.calls_per_second
+                    ),
+                );
+                bucket.throttled = 0;
+                bucket.logged_at = Some(now);
+            }
+            wait
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[async_trait]
+impl<S: RpcSender + Send + Sync> RpcSender for RateLimitedRpcClient<S> {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        self.acquire().await;
+        self.sender.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.sender.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.sender.url()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(capacity: f64, now: Instant) -> TokenBucket {
+        TokenBucket {
+            tokens: capacity,
+            capacity,
+            refilled_at: now,
+            throttled: 0,
+            logged_at: None,
+        }
+    }
+
+    #[test]
+    fn bursts_up_to_capacity_then_waits() {
+        let start = Instant::now();
+        let mut bucket = bucket(2.0, start);
+        assert_eq!(bucket.take(start, 2.0), None);
+        assert_eq!(bucket.take(start, 2.0), None);
+        assert_eq!(bucket.take(start, 2.0), Some(Duration::from_millis(500)));
+        assert_eq!(bucket.take(start, 2.0), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = bucket(2.0, start);
+        bucket.take(start, 2.0);
+        bucket.take(start, 2.0);
+        assert_eq!(bucket.take(start + Duration::from_millis(500), 2.0), None);
+    }
+
+    #[test]
+    fn refill_is_capped_at_capacity() {
+        let start = Instant::now();
+        let mut bucket = bucket(2.0, start);
+        let later = start + Duration::from_secs(60);
+        assert_eq!(bucket.take(later, 2.0), None);
+        assert_eq!(bucket.take(later, 2.0), None);
+        assert!(bucket.take(later, 2.0).is_some());
+    }
+}
@@ -1,11 +1,14 @@
+use tracing::info;
+
 use crate::{
+    error::MineError,
     utils::{amount_u64_to_string, get_config},
     Miner,
 };
 
 impl Miner {
-    pub async fn rewards(&self) {
-        let config = get_config(&self.rpc_client).await;
+    pub async fn rewards(&self) -> Result<(), MineError> {
+        let config = get_config(&self.rpc_client).await?;
         let base_reward_rate = config.base_reward_rate;
 
         let mut s = format!(
@@ -23,6 +26,7 @@ impl Miner {
                 amount_u64_to_string(reward_rate)
             );
         }
-        println!("{}", s);
+        info!("{}", s);
+        Ok(())
     }
 }
@@ -0,0 +1,175 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_request::{RpcError, RpcRequest},
+};
+use solana_rpc_client::{
+    http_sender::HttpSender,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+
+const ERROR_WINDOW: Duration = Duration::from_secs(300);
+const LATENCY_INTERVAL: Duration = Duration::from_secs(60);
+const BACKOFF_DELAY: u64 = 100;
+const NODE_UNHEALTHY: i64 = -32005;
+
+struct Endpoint {
+    sender: HttpSender,
+    history: Mutex<VecDeque<(Instant, bool)>>,
+}
+
+impl Endpoint {
+    fn record(&self, ok: bool) {
+        let mut history = self.history.lock().unwrap();
+        history.push_back((Instant::now(), ok));
+        prune(&mut history);
+    }
+
+    fn error_rate(&self) -> f64 {
+        let mut history = self.history.lock().unwrap();
+        prune(&mut history);
+        if history.is_empty() {
+            return 0.0;
+        }
+        let errors = history.iter().filter(|(_, ok)| !ok).count();
+        errors as f64 / history.len() as f64
+    }
+}
+
+/// An rpc sender that fails over between multiple endpoints, preferring the ones
+/// with the lowest error rate over the last 5 minutes, then the lowest latency.
+pub struct RpcClientPool {
+    endpoints: Vec<Endpoint>,
+    latencies: Arc<RwLock<Vec<(String, Duration)>>>,
+}
+
+impl RpcClientPool {
+    /// Creates the pool and starts measuring the latency of each endpoint in the background.
+    pub fn new(urls: Vec<String>) -> Self {
+        assert!(!urls.is_empty(), "At least one rpc url is required");
+        let latencies = Arc::new(RwLock::new(vec![]));
+        tokio::spawn(sample_latencies(urls.clone(), latencies.clone()));
+        Self {
+            endpoints: urls
+                .into_iter()
+                .map(|url| Endpoint {
+                    sender: HttpSender::new(url),
+                    history: Mutex::new(VecDeque::new()),
+                })
+                .collect(),
+            latencies,
+        }
+    }
+
+    fn ranked(&self) -> Vec<&Endpoint> {
+        let latencies = self.latencies.read().unwrap();
+        let latency = |endpoint: &Endpoint| {
+            let url = endpoint.sender.url();
+            latencies
+                .iter()
+                .find(|(u, _)| u.eq(&url))
+                .map_or(Duration::MAX, |(_, latency)| *latency)
+        };
+        let mut endpoints: Vec<&Endpoint> = self.endpoints.iter().collect();
+        endpoints.sort_by(|a, b| {
+            a.error_rate()
+                .total_cmp(&b.error_rate())
+                .then_with(|| latency(a).cmp(&latency(b)))
+        });
+        endpoints
+    }
+}
+
+#[async_trait]
+impl RpcSender for RpcClientPool {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let mut last_err = None;
+        for (attempt, endpoint) in self.ranked().into_iter().enumerate() {
+            if attempt.gt(&0) {
+                let delay = BACKOFF_DELAY.saturating_mul(1 << (attempt - 1).min(6));
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            match endpoint.sender.send(request, params.clone()).await {
+                Ok(result) => {
+                    endpoint.record(true);
+                    return Ok(result);
+                }
+                Err(err) => {
+                    // Errors returned by a healthy node are not retried elsewhere
+                    if !is_endpoint_error(&err) {
+                        endpoint.record(true);
+                        return Err(err);
+                    }
+                    endpoint.record(false);
+                    last_err = Some(err);
+                }
+            }
+        }
+        Err(last_err.unwrap())
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.endpoints
+            .iter()
+            .fold(RpcTransportStats::default(), |mut acc, endpoint| {
+                let stats = endpoint.sender.get_transport_stats();
+                acc.request_count += stats.request_count;
+                acc.elapsed_time += stats.elapsed_time;
+                acc.rate_limited_time += stats.rate_limited_time;
+                acc
+            })
+    }
+
+    fn url(&self) -> String {
+        self.ranked()[0].sender.url()
+    }
+}
+
+/// Pings every endpoint with getHealth once a minute, keeping the latencies sorted fastest first.
+/// Endpoints that fail the health check are left out.
+async fn sample_latencies(urls: Vec<String>, latencies: Arc<RwLock<Vec<(String, Duration)>>>) {
+    let senders: Vec<HttpSender> = urls.into_iter().map(HttpSender::new).collect();
+    let mut interval = tokio::time::interval(LATENCY_INTERVAL);
+    loop {
+        interval.tick().await;
+        let mut samples = vec![];
+        for sender in senders.iter() {
+            let timer = Instant::now();
+            if sender
+                .send(RpcRequest::GetHealth, Value::Null)
+                .await
+                .is_ok()
+            {
+                samples.push((sender.url(), timer.elapsed()));
+            }
+        }
+        samples.sort_by_key(|(_, latency)| *latency);
+        *latencies.write().unwrap() = samples;
+    }
+}
+
+fn prune(history: &mut VecDeque<(Instant, bool)>) {
+    while let Some((at, _)) = history.front() {
+        if at.elapsed().le(&ERROR_WINDOW) {
+            break;
+        }
+        history.pop_front();
+    }
+}
+
+fn is_endpoint_error(err: &ClientError) -> bool {
+    match err.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            code.eq(&NODE_UNHEALTHY)
+        }
+        _ => false,
+    }
+}
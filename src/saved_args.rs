@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use tracing::info;
+
+use crate::{
+    args::{MineArgs, SaveConfigArgs},
+    error::MineError,
+    Miner,
+};
+
+impl Miner {
+    pub fn save_config(&self, args: SaveConfigArgs) -> Result<(), MineError> {
+        self.save_args(&args.mine, &args.output).map_err(|err| {
+            MineError::InvalidArgs(format!(
+                "Could not write mine options to `{}`: {}",
+                args.output, err
+            ))
+        })?;
+        info!("Wrote mine options to {}", args.output);
+        Ok(())
+    }
+
+    /// Writes the mine options to a file, as JSON if the path ends in `.json` and TOML otherwise.
+    pub fn save_args(&self, args: &MineArgs, path: &str) -> Result<(), String> {
+        let contents = if is_json(path) {
+            serde_json::to_string_pretty(args).map_err(|err| err.to_string())?
+        } else {
+            toml::to_string_pretty(args).map_err(|err| err.to_string())?
+        };
+        if let Some(dir) = Path::new(path).parent() {
+            std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(path, contents).map_err(|err| err.to_string())
+    }
+}
+
+/// Whether a file of mine options is JSON rather than TOML.
+pub fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map_or(false, |ext| ext.eq("json"))
+}
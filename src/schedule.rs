@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use cron::Schedule;
+
+/// Parses a cron expression. Standard five field expressions are accepted as well as the
+/// cron crate's six and seven field ones, which start with seconds.
+pub fn parse_schedule(expression: &str) -> Result<Schedule, String> {
+    let expression = expression.trim();
+    let expression = if expression.split_whitespace().count().eq(&5) {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&expression).map_err(|err| format!("Invalid schedule: {}", err))
+}
+
+/// Returns when the next mining window opens, or None if one is open now. Windows are the
+/// hours the schedule fires in, so `0 0-6 * * *` mines from midnight until 7am UTC.
+pub fn next_window(schedule: &Schedule, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let hour_start = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+    let next = schedule
+        .after(&(hour_start - Duration::seconds(1)))
+        .next()?;
+    if next.lt(&(hour_start + Duration::hours(1))) {
+        return None;
+    }
+    Some(next.duration_trunc(Duration::hours(1)).unwrap_or(next))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_five_and_six_field_expressions() {
+        assert!(parse_schedule("0 0-6 * * *").is_ok());
+        assert!(parse_schedule(" 0 0 0-6 * * * ").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        let err = parse_schedule("every night").unwrap_err();
+        assert!(err.starts_with("Invalid schedule"), "{}", err);
+    }
+
+    #[test]
+    fn window_is_open_during_scheduled_hours() {
+        let schedule = parse_schedule("0 0-6 * * *").unwrap();
+        assert_eq!(next_window(&schedule, at(0, 0)), None);
+        assert_eq!(next_window(&schedule, at(3, 30)), None);
+        assert_eq!(next_window(&schedule, at(6, 59)), None);
+    }
+
+    #[test]
+    fn next_window_opens_at_the_scheduled_hour() {
+        let schedule = parse_schedule("0 0-6 * * *").unwrap();
+        assert_eq!(
+            next_window(&schedule, at(7, 0)),
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            next_window(&schedule, at(23, 59)),
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn windows_fire_on_any_minute_of_the_hour() {
+        let schedule = parse_schedule("30 2 * * *").unwrap();
+        assert_eq!(next_window(&schedule, at(2, 10)), None);
+        assert_eq!(next_window(&schedule, at(1, 0)), Some(at(2, 0)));
+    }
+}
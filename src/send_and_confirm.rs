@@ -1,15 +1,17 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
 use colored::*;
+use serde::{Deserialize, Serialize};
 use solana_client::{
     client_error::{ClientError, ClientErrorKind, Result as ClientResult},
-    rpc_config::RpcSendTransactionConfig,
+    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
 };
 use solana_program::{
     instruction::Instruction,
     native_token::{lamports_to_sol, sol_to_lamports},
 };
-use solana_rpc_client::spinner;
 use solana_sdk::{
     commitment_config::CommitmentLevel,
     compute_budget::ComputeBudgetInstruction,
@@ -17,22 +19,110 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use solana_transaction_status::{TransactionConfirmationStatus, UiTransactionEncoding};
+use tracing::{error, info};
 
-use crate::Miner;
+use crate::{error::MineError, tui, Miner};
 
-const MIN_SOL_BALANCE: f64 = 0.005;
+/// Default balance below which the fee payer is considered out of SOL.
+pub const MIN_SOL_BALANCE: f64 = 0.005;
 
 const RPC_RETRIES: usize = 0;
-const _SIMULATION_RETRIES: usize = 4;
-const GATEWAY_RETRIES: usize = 250;
 const CONFIRM_RETRIES: usize = 8;
 
 const CONFIRM_DELAY: u64 = 500;
-const GATEWAY_DELAY: u64 = 0; //300;
 
+/// Delay before the first rebroadcast, doubled on each retry after.
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Attempts that rebroadcast the same signed transaction before it is signed again with a
+/// fresh blockhash and priority fee.
+const RESIGN_INTERVAL: usize = 5;
+
+/// The compute units a transaction requests, and how its compute unit price is picked.
 pub enum ComputeBudget {
-    Dynamic,
+    /// Request this many compute units, at the fixed priority fee.
     Fixed(u32),
+    /// Request `cu_limit` compute units, priced at the `percentile` of recent prioritization
+    /// fees. `max_lamports` caps the total priority fee of this estimate; the
+    /// --priority-fee-cap-per-pass cap is applied after it, so the lower of the two wins.
+    Dynamic {
+        cu_limit: u32,
+        percentile: u8,
+        max_lamports: Option<u64>,
+    },
+}
+
+impl ComputeBudget {
+    /// Returns the compute unit limit to request.
+    pub fn cu_limit(&self) -> u32 {
+        match self {
+            ComputeBudget::Fixed(cu_limit) | ComputeBudget::Dynamic { cu_limit, .. } => *cu_limit,
+        }
+    }
+}
+
+/// How a transaction is rebroadcast until it confirms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum RetryPolicy {
+    /// Rebroadcast up to --max-retries times, doubling the delay each time.
+    Backoff,
+    /// Rebroadcast every 500ms for up to 10 attempts, skipping preflight.
+    Aggressive,
+    /// Rebroadcast 3 times, 2s apart, after preflight checks.
+    Conservative,
+    /// Send once and return without waiting for confirmation.
+    Once,
+}
+
+impl RetryPolicy {
+    /// Returns how many times to rebroadcast.
+    fn retries(&self, max_retries: usize) -> usize {
+        match self {
+            RetryPolicy::Backoff => max_retries,
+            RetryPolicy::Aggressive => 9,
+            RetryPolicy::Conservative => 3,
+            RetryPolicy::Once => 0,
+        }
+    }
+
+    /// Returns how long to wait after a failed attempt before rebroadcasting.
+    fn backoff(&self, retries: usize) -> Duration {
+        match self {
+            RetryPolicy::Backoff => {
+                RETRY_BACKOFF.saturating_mul(2u32.saturating_pow(retries as u32))
+            }
+            RetryPolicy::Aggressive | RetryPolicy::Once => Duration::ZERO,
+            RetryPolicy::Conservative => Duration::from_secs(2),
+        }
+    }
+
+    /// Returns how many times to poll for confirmation after each attempt.
+    fn confirm_polls(&self) -> usize {
+        match self {
+            // Polls once per attempt, so a rebroadcast goes out every poll
+            RetryPolicy::Aggressive => 1,
+            RetryPolicy::Once => 0,
+            RetryPolicy::Backoff | RetryPolicy::Conservative => CONFIRM_RETRIES,
+        }
+    }
+
+    fn skip_preflight(&self) -> bool {
+        !self.eq(&RetryPolicy::Conservative)
+    }
+}
+
+impl std::fmt::Display for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = match self {
+            RetryPolicy::Backoff => "backoff",
+            RetryPolicy::Aggressive => "aggressive",
+            RetryPolicy::Conservative => "conservative",
+            RetryPolicy::Once => "once",
+        };
+        write!(f, "{}", policy)
+    }
 }
 
 impl Miner {
@@ -41,38 +131,52 @@ impl Miner {
         ixs: &[Instruction],
         compute_budget: ComputeBudget,
         skip_confirm: bool,
-    ) -> ClientResult<Signature> {
-        let signer = self.signer();
+        policy: RetryPolicy,
+    ) -> Result<Signature, MineError> {
+        let signer = self.try_signer()?;
+        let fee_payer = self.try_fee_payer()?;
+        self.send_and_confirm_with_signer(
+            signer.as_ref(),
+            fee_payer.as_ref(),
+            ixs,
+            compute_budget,
+            skip_confirm,
+            policy,
+        )
+        .await
+    }
+
+    pub async fn send_and_confirm_with_signer(
+        &self,
+        signer: &dyn Signer,
+        fee_payer: &dyn Signer,
+        ixs: &[Instruction],
+        compute_budget: ComputeBudget,
+        skip_confirm: bool,
+        policy: RetryPolicy,
+    ) -> Result<Signature, MineError> {
         let client = self.rpc_client.clone();
-        let fee_payer = self.fee_payer();
 
         // Return error, if balance is zero
         if let Ok(balance) = client.get_balance(&fee_payer.pubkey()).await {
-            if balance <= sol_to_lamports(MIN_SOL_BALANCE) {
-                panic!(
-                    "{} Insufficient balance: {} SOL\nPlease top up with at least {} SOL",
-                    "ERROR".bold().red(),
+            if balance <= sol_to_lamports(self.min_sol_balance) {
+                error!(
+                    "Insufficient balance: {} SOL. Please top up with at least {} SOL",
                     lamports_to_sol(balance),
-                    MIN_SOL_BALANCE
+                    self.min_sol_balance
                 );
+                return Err(MineError::InsufficientFunds);
             }
         }
 
         // Set compute budget
         let mut final_ixs = vec![];
-        match compute_budget {
-            ComputeBudget::Dynamic => {
-                // TODO simulate
-                final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(1_400_000))
-            }
-            ComputeBudget::Fixed(cus) => {
-                final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(cus))
-            }
-        }
+        let cu_limit = compute_budget.cu_limit();
+        final_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(cu_limit));
 
         // Set compute unit price
         final_ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
-            self.priority_fee.unwrap_or(0),
+            self.static_compute_unit_price(),
         ));
 
         // Add in user instructions
@@ -80,57 +184,66 @@ impl Miner {
 
         // Build tx
         let send_cfg = RpcSendTransactionConfig {
-            skip_preflight: true,
+            skip_preflight: policy.skip_preflight(),
             preflight_commitment: Some(CommitmentLevel::Confirmed),
             encoding: Some(UiTransactionEncoding::Base64),
             max_retries: Some(RPC_RETRIES),
             min_context_slot: None,
         };
+        let progress_bar = tui::new_progress_bar();
+        let signers: Vec<&dyn Signer> = if signer.pubkey() == fee_payer.pubkey() {
+            vec![signer]
+        } else {
+            vec![signer, fee_payer]
+        };
         let mut tx = Transaction::new_with_payer(&final_ixs, Some(&fee_payer.pubkey()));
 
+        // Every signature sent so far, any of which may still land
+        let mut sigs: Vec<Signature> = vec![];
+
         // Submit tx
-        let progress_bar = spinner::new_progress_bar();
-        let mut attempts = 0;
+        let mut retries = 0;
         loop {
-            progress_bar.set_message(format!("Submitting transaction... (attempt {})", attempts,));
-
-            // Sign tx with a new blockhash
-            if attempts % 5 == 0 {
-                // Reset the compute unit price
-                if self.dynamic_fee_strategy.is_some() {
-                    let fee = self.dynamic_fee().await;
-                    final_ixs.remove(1);
-                    final_ixs.insert(1, ComputeBudgetInstruction::set_compute_unit_price(fee));
+            progress_bar.set_message(format!("Submitting transaction... (attempt {})", retries));
+
+            // Sign tx with a new blockhash and fee every few attempts, keeping the same signature
+            // for the rebroadcasts in between
+            if retries % RESIGN_INTERVAL == 0 {
+                if let Some(fee) = self.capped_compute_unit_price(&compute_budget).await? {
+                    final_ixs[1] = ComputeBudgetInstruction::set_compute_unit_price(fee);
                     progress_bar.println(format!("  Priority fee: {} microlamports", fee));
                 }
-
-                // Resign the tx
                 let (hash, _slot) = client
                     .get_latest_blockhash_with_commitment(self.rpc_client.commitment())
-                    .await
-                    .unwrap();
-                if signer.pubkey() == fee_payer.pubkey() {
-                    tx.sign(&[&signer], hash);
-                } else {
-                    tx.sign(&[&signer, &fee_payer], hash);
+                    .await?;
+                tx = Transaction::new_with_payer(&final_ixs, Some(&fee_payer.pubkey()));
+                let timer = Instant::now();
+                tx.sign(&signers, hash);
+                if self.ledger {
+                    progress_bar.println(format!(
+                        "  Signed with Ledger in {} ms",
+                        timer.elapsed().as_millis()
+                    ));
                 }
+                sigs.push(tx.signatures[0]);
             }
+            let sig = tx.signatures[0];
 
             // Send transaction
             match client.send_transaction_with_config(&tx, send_cfg).await {
-                Ok(sig) => {
+                Ok(_) => {
                     // Skip confirmation
-                    if skip_confirm {
+                    if skip_confirm || policy.eq(&RetryPolicy::Once) {
                         progress_bar.finish_with_message(format!("Sent: {}", sig));
                         return Ok(sig);
                     }
 
                     // Confirm transaction
-                    for _ in 0..CONFIRM_RETRIES {
-                        std::thread::sleep(Duration::from_millis(CONFIRM_DELAY));
-                        match client.get_signature_statuses(&[sig]).await {
+                    for _ in 0..policy.confirm_polls() {
+                        tokio::time::sleep(Duration::from_millis(CONFIRM_DELAY)).await;
+                        match client.get_signature_statuses(&sigs).await {
                             Ok(signature_statuses) => {
-                                for status in signature_statuses.value {
+                                for (sig, status) in sigs.iter().zip(signature_statuses.value) {
                                     if let Some(status) = status {
                                         if let Some(err) = status.err {
                                             progress_bar.finish_with_message(format!(
@@ -138,22 +251,26 @@ impl Miner {
                                                 "ERROR".bold().red(),
                                                 err
                                             ));
-                                            return Err(ClientError {
-                                                request: None,
-                                                kind: ClientErrorKind::Custom(err.to_string()),
-                                            });
+                                            return Err(err.into());
                                         }
                                         if let Some(confirmation) = status.confirmation_status {
                                             match confirmation {
                                                 TransactionConfirmationStatus::Processed => {}
                                                 TransactionConfirmationStatus::Confirmed
                                                 | TransactionConfirmationStatus::Finalized => {
-                                                    progress_bar.finish_with_message(format!(
-                                                        "{} {}",
-                                                        "OK".bold().green(),
-                                                        sig
-                                                    ));
-                                                    return Ok(sig);
+                                                    progress_bar.finish_with_message(
+                                                        if retries.gt(&0) {
+                                                            format!(
+                                                                "{} {} (confirmed after {} retries)",
+                                                                "OK".bold().green(),
+                                                                sig,
+                                                                retries
+                                                            )
+                                                        } else {
+                                                            format!("{} {}", "OK".bold().green(), sig)
+                                                        },
+                                                    );
+                                                    return Ok(*sig);
                                                 }
                                             }
                                         }
@@ -173,83 +290,83 @@ impl Miner {
                     }
                 }
 
-                // Handle submit errors
+                // Handle submit errors, giving up on ones a rebroadcast cannot fix
                 Err(err) => {
-                    progress_bar.set_message(format!(
-                        "{}: {}",
-                        "ERROR".bold().red(),
-                        err.kind().to_string()
-                    ));
+                    let err = MineError::from(err);
+                    if err.is_fatal() {
+                        progress_bar.finish_with_message(format!(
+                            "{}: {}",
+                            "ERROR".bold().red(),
+                            err
+                        ));
+                        return Err(err);
+                    }
+                    progress_bar.set_message(format!("{}: {}", "ERROR".bold().red(), err));
                 }
             }
 
-            // Retry
-            std::thread::sleep(Duration::from_millis(GATEWAY_DELAY));
-            attempts += 1;
-            if attempts > GATEWAY_RETRIES {
-                progress_bar.finish_with_message(format!("{}: Max retries", "ERROR".bold().red()));
-                return Err(ClientError {
-                    request: None,
-                    kind: ClientErrorKind::Custom("Max retries".into()),
+            // Rebroadcast, waiting as long as the policy asks
+            if retries.ge(&policy.retries(self.max_retries)) {
+                progress_bar.finish_with_message(format!(
+                    "{}: Transaction dropped after {} retries",
+                    "ERROR".bold().red(),
+                    retries
+                ));
+                return Err(MineError::TransactionDropped {
+                    signature: sig,
+                    retries,
                 });
             }
+            tokio::time::sleep(policy.backoff(retries)).await;
+            retries += 1;
         }
     }
 
-    // TODO
-    fn _simulate(&self) {
+    /// Simulates a transaction without verifying signatures, printing the program logs
+    /// if it fails.
+    pub async fn simulate(
+        &self,
+        fee_payer: &dyn Signer,
+        ixs: &[Instruction],
+        compute_budget: ComputeBudget,
+    ) -> ClientResult<()> {
+        // Build tx
+        let cu_limit = compute_budget.cu_limit();
+        let mut final_ixs = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(cu_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.static_compute_unit_price()),
+        ];
+        final_ixs.extend_from_slice(ixs);
+        let tx = Transaction::new_with_payer(&final_ixs, Some(&fee_payer.pubkey()));
 
         // Simulate tx
-        // let mut sim_attempts = 0;
-        // 'simulate: loop {
-        //     let sim_res = client
-        //         .simulate_transaction_with_config(
-        //             &tx,
-        //             RpcSimulateTransactionConfig {
-        //                 sig_verify: false,
-        //                 replace_recent_blockhash: true,
-        //                 commitment: Some(self.rpc_client.commitment()),
-        //                 encoding: Some(UiTransactionEncoding::Base64),
-        //                 accounts: None,
-        //                 min_context_slot: Some(slot),
-        //                 inner_instructions: false,
-        //             },
-        //         )
-        //         .await;
-        //     match sim_res {
-        //         Ok(sim_res) => {
-        //             if let Some(err) = sim_res.value.err {
-        //                 println!("Simulaton error: {:?}", err);
-        //                 sim_attempts += 1;
-        //             } else if let Some(units_consumed) = sim_res.value.units_consumed {
-        //                 if dynamic_cus {
-        //                     println!("Dynamic CUs: {:?}", units_consumed);
-        //                     let cu_budget_ix = ComputeBudgetInstruction::set_compute_unit_limit(
-        //                         units_consumed as u32 + 1000,
-        //                     );
-        //                     let cu_price_ix =
-        //                         ComputeBudgetInstruction::set_compute_unit_price(self.priority_fee);
-        //                     let mut final_ixs = vec![];
-        //                     final_ixs.extend_from_slice(&[cu_budget_ix, cu_price_ix]);
-        //                     final_ixs.extend_from_slice(ixs);
-        //                     tx = Transaction::new_with_payer(&final_ixs, Some(&signer.pubkey()));
-        //                 }
-        //                 break 'simulate;
-        //             }
-        //         }
-        //         Err(err) => {
-        //             println!("Simulaton error: {:?}", err);
-        //             sim_attempts += 1;
-        //         }
-        //     }
-
-        //     // Abort if sim fails
-        //     if sim_attempts.gt(&SIMULATION_RETRIES) {
-        //         return Err(ClientError {
-        //             request: None,
-        //             kind: ClientErrorKind::Custom("Simulation failed".into()),
-        //         });
-        //     }
-        // }
+        let sim_res = self
+            .rpc_client
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(self.rpc_client.commitment()),
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )
+            .await?;
+        match sim_res.value.err {
+            None => Ok(()),
+            Some(err) => {
+                if !self.is_json() {
+                    error!("Simulation failed: {}", err);
+                    for log in sim_res.value.logs.unwrap_or_default() {
+                        info!("  {}", log);
+                    }
+                }
+                Err(ClientError {
+                    request: None,
+                    kind: ClientErrorKind::Custom(format!("Simulation failed: {}", err)),
+                })
+            }
+        }
     }
 }
@@ -0,0 +1,216 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
+};
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+
+use crate::{
+    csv_log::CsvLog,
+    db::Database,
+    difficulty_histogram::DifficultyHistogram,
+    error::MineError,
+    markdown_export::MarkdownExport,
+    metrics::MetricsState,
+    notify::{DiscordNotifier, TelegramNotifier},
+    price_feed::OrePrice,
+    tpu::QuicTpuClient,
+};
+
+pub struct Session {
+    pub id: String,
+    pub started_at: Instant,
+    pub stats: Mutex<SessionStats>,
+    pub difficulties: Mutex<DifficultyHistogram>,
+    pub db: Option<Mutex<Database>>,
+    pub log: Option<Mutex<CsvLog>>,
+    pub export: Option<Mutex<MarkdownExport>>,
+    pub metrics: Option<Arc<Mutex<MetricsState>>>,
+    pub notifier: Option<DiscordNotifier>,
+    pub telegram: Option<TelegramNotifier>,
+    pub ore_price: Option<OrePrice>,
+    pub funding_wallet: Option<Keypair>,
+    pub tpu_client: Option<QuicTpuClient>,
+    pub shutdown: Arc<AtomicBool>,
+    /// Error that stopped the session, returned once the session totals are printed.
+    pub exit_error: Mutex<Option<MineError>>,
+    pub control: Arc<MinerControl>,
+}
+
+/// The session to pick up the totals of.
+pub enum Resume {
+    /// The most recent session recorded for the first wallet.
+    Latest,
+    Session(String),
+}
+
+impl Session {
+    /// Starts a new session, or resumes the totals of one recorded in the database.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        db: Option<Database>,
+        log: Option<CsvLog>,
+        export: Option<MarkdownExport>,
+        resume: Option<Resume>,
+        wallets: &[Pubkey],
+        metrics: Option<Arc<Mutex<MetricsState>>>,
+        notifier: Option<DiscordNotifier>,
+        telegram: Option<TelegramNotifier>,
+        ore_price: Option<OrePrice>,
+        funding_wallet: Option<Keypair>,
+        tpu_client: Option<QuicTpuClient>,
+    ) -> Self {
+        let mut stats = SessionStats::default();
+        let id = match (&db, resume) {
+            (Some(db), Some(Resume::Latest)) => wallets
+                .first()
+                .and_then(|first| db.latest_session_id(&first.to_string()).ok().flatten()),
+            (Some(_), Some(Resume::Session(session_id))) => Some(session_id),
+            _ => None,
+        };
+        if let (Some(db), Some(session_id)) = (&db, &id) {
+            for wallet in wallets {
+                if let Ok(totals) = db.session_totals(session_id, &wallet.to_string()) {
+                    stats.wallets.insert(*wallet, totals);
+                }
+            }
+        }
+        Self {
+            id: id.unwrap_or_else(new_session_id),
+            started_at: Instant::now(),
+            stats: Mutex::new(stats),
+            difficulties: Mutex::new(DifficultyHistogram::default()),
+            db: db.map(Mutex::new),
+            log: log.map(Mutex::new),
+            export: export.map(Mutex::new),
+            metrics,
+            notifier,
+            telegram,
+            ore_price,
+            funding_wallet,
+            tpu_client,
+            shutdown: Arc::new(AtomicBool::new(false)),
+            exit_error: Mutex::new(None),
+            control: Arc::new(MinerControl::default()),
+        }
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+
+    /// Stops every wallet after its current pass, failing with the first error once the
+    /// session totals are printed.
+    pub fn exit(&self, err: MineError) {
+        self.exit_error.lock().unwrap().get_or_insert(err);
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Requests from outside the mining loop, such as over gRPC.
+#[derive(Debug, Default)]
+pub struct MinerControl {
+    paused: AtomicBool,
+    threads: AtomicU64,
+}
+
+impl MinerControl {
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_threads(&self, threads: u64) {
+        self.threads.store(threads, Ordering::Relaxed);
+    }
+
+    /// Returns the thread count last requested, if any.
+    pub fn requested_threads(&self) -> Option<u64> {
+        Some(self.threads.load(Ordering::Relaxed)).filter(|threads| threads.gt(&0))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WalletStats {
+    pub passes: u64,
+    pub ore_mined: u64,
+    pub sol_used: u64,
+    pub ore_staked: u64,
+    pub low_difficulty_skips: u64,
+    pub est_ore_per_hour: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct SessionStats {
+    pub wallets: BTreeMap<Pubkey, WalletStats>,
+}
+
+impl SessionStats {
+    pub fn record(&mut self, wallet: Pubkey, ore_mined: u64, sol_used: u64) -> WalletStats {
+        let stats = self.wallets.entry(wallet).or_default();
+        stats.passes = stats.passes.saturating_add(1);
+        stats.ore_mined = stats.ore_mined.saturating_add(ore_mined);
+        stats.sol_used = stats.sol_used.saturating_add(sol_used);
+        *stats
+    }
+
+    pub fn record_stake(&mut self, wallet: Pubkey, amount: u64) -> WalletStats {
+        let stats = self.wallets.entry(wallet).or_default();
+        stats.ore_staked = stats.ore_staked.saturating_add(amount);
+        *stats
+    }
+
+    pub fn record_low_difficulty_skip(&mut self, wallet: Pubkey) -> WalletStats {
+        let stats = self.wallets.entry(wallet).or_default();
+        stats.low_difficulty_skips = stats.low_difficulty_skips.saturating_add(1);
+        *stats
+    }
+
+    pub fn record_rate(&mut self, wallet: Pubkey, ore_per_hour: f64) -> WalletStats {
+        let stats = self.wallets.entry(wallet).or_default();
+        stats.est_ore_per_hour = ore_per_hour;
+        *stats
+    }
+
+    pub fn total(&self) -> WalletStats {
+        self.wallets
+            .values()
+            .fold(WalletStats::default(), |acc, stats| WalletStats {
+                passes: acc.passes.saturating_add(stats.passes),
+                ore_mined: acc.ore_mined.saturating_add(stats.ore_mined),
+                sol_used: acc.sol_used.saturating_add(stats.sol_used),
+                ore_staked: acc.ore_staked.saturating_add(stats.ore_staked),
+                low_difficulty_skips: acc
+                    .low_difficulty_skips
+                    .saturating_add(stats.low_difficulty_skips),
+                est_ore_per_hour: acc.est_ore_per_hour + stats.est_ore_per_hour,
+            })
+    }
+}
+
+/// A snapshot of the mining session, updated after every pass.
+#[derive(Clone, Debug, Default)]
+pub struct MinerStats {
+    pub pass: u64,
+    pub session_ore_mined: f64,
+    pub session_sol_used: f64,
+    pub best_difficulty_this_pass: u32,
+    pub session_best_difficulty: u32,
+    pub current_sol_balance: f64,
+    pub current_staked_balance: f64,
+    pub est_ore_per_hour: f64,
+    pub uptime_secs: u64,
+}
+
+/// Returns a random id to tell sessions apart in the logs and the database.
+fn new_session_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
@@ -0,0 +1,46 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::{args::OutputFormat, output::print_warning};
+
+/// Sets the shutdown flag on SIGINT or SIGTERM, and exits the process if the current
+/// pass has not wrapped up within the timeout.
+pub fn listen_for_shutdown(shutdown: Arc<AtomicBool>, timeout: u64, output: OutputFormat) {
+    tokio::spawn(async move {
+        wait_for_signal().await;
+        shutdown.store(true, Ordering::Relaxed);
+        print_warning(
+            output,
+            &format!(
+                "Shutting down after the current pass (timeout {} sec)...",
+                timeout
+            ),
+        );
+        tokio::time::sleep(Duration::from_secs(timeout)).await;
+        print_warning(output, "Shutdown timed out");
+        std::process::exit(1);
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+        let _ = tokio::signal::ctrl_c().await;
+        return;
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -1,19 +1,31 @@
 use std::str::FromStr;
 
+#[cfg(feature = "cli")]
+use serde_json::json;
+#[cfg(feature = "cli")]
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signature::Signer;
+#[cfg(feature = "cli")]
+use tracing::info;
 
+#[cfg(feature = "cli")]
 use crate::{
-    args::StakeArgs, cu_limits::CU_LIMIT_CLAIM, send_and_confirm::ComputeBudget,
-    utils::amount_f64_to_u64, Miner,
+    args::StakeArgs,
+    error::MineError,
+    output::print_json_event,
+    utils::{amount_f64_to_u64, amount_u64_to_f64, amount_u64_to_string, get_proof_with_authority},
 };
+use crate::{cu_limits::CU_LIMIT_CLAIM, Miner};
 
+#[cfg(feature = "cli")]
 impl Miner {
-    pub async fn stake(&self, args: StakeArgs) {
+    pub async fn stake(&self, args: StakeArgs) -> Result<(), MineError> {
         // Get signer
-        let signer = self.signer();
+        let signer = self.try_signer()?;
         let sender = match args.sender {
-            Some(sender) => Pubkey::from_str(&sender).expect("Failed to parse sender address"),
+            Some(sender) => Pubkey::from_str(&sender).map_err(|_| {
+                MineError::InvalidArgs(format!("Invalid sender address: {}", sender))
+            })?,
             None => spl_associated_token_account::get_associated_token_address(
                 &signer.pubkey(),
                 &ore_api::consts::MINT_ADDRESS,
@@ -21,23 +33,119 @@ impl Miner {
         };
 
         // Get token account
-        let Ok(Some(token_account)) = self.rpc_client.get_token_account(&sender).await else {
-            println!("Failed to fetch token account");
-            return;
+        let Some(token_account) = self.rpc_client.get_token_account(&sender).await? else {
+            return Err(MineError::InvalidArgs(format!(
+                "Token account {} does not exist",
+                sender
+            )));
         };
 
-        // Parse amount
+        // Parse amount, staking the max less the liquid reserve by default
+        let balance = u64::from_str(token_account.token_amount.amount.as_str())
+            .map_err(|_| MineError::Unknown("Failed to parse token balance".to_string()))?;
         let amount: u64 = if let Some(amount) = args.amount {
             amount_f64_to_u64(amount)
         } else {
-            u64::from_str(token_account.token_amount.amount.as_str())
-                .expect("Failed to parse token balance")
+            balance.saturating_sub(amount_f64_to_u64(args.keep_liquid))
         };
+        if amount.eq(&0) {
+            return Err(MineError::InvalidArgs("Nothing to stake".to_string()));
+        }
+        if amount.gt(&balance) {
+            return Err(MineError::InvalidArgs(format!(
+                "Cannot stake {} ORE. Token account balance is {} ORE",
+                amount_u64_to_string(amount),
+                amount_u64_to_string(balance)
+            )));
+        }
+        let stake_before = get_proof_with_authority(&self.rpc_client, signer.pubkey())
+            .await?
+            .balance;
 
         // Send tx
         let ix = ore_api::instruction::stake(signer.pubkey(), sender, amount);
-        self.send_and_confirm(&[ix], ComputeBudget::Fixed(CU_LIMIT_CLAIM), false)
+        self.send_and_confirm(
+            &[ix],
+            self.compute_budget(CU_LIMIT_CLAIM),
+            false,
+            self.retry_policy,
+        )
+        .await?;
+
+        // Confirm the new stake
+        let stake_after = get_proof_with_authority(&self.rpc_client, signer.pubkey())
+            .await?
+            .balance;
+        let balance_after = self
+            .rpc_client
+            .get_token_account_balance(&sender)
             .await
-            .ok();
+            .ok()
+            .and_then(|balance| u64::from_str(balance.amount.as_str()).ok())
+            .unwrap_or(balance.saturating_sub(amount));
+        if self.is_json() {
+            print_json_event(
+                "stake",
+                json!({
+                    "amount": amount_u64_to_f64(amount),
+                    "balance_before": amount_u64_to_f64(balance),
+                    "balance_after": amount_u64_to_f64(balance_after),
+                    "stake_before": amount_u64_to_f64(stake_before),
+                    "stake_after": amount_u64_to_f64(stake_after),
+                }),
+            );
+        } else {
+            info!("Staked: {} ORE", amount_u64_to_string(amount));
+            info!(
+                "  Balance: {} ORE -> {} ORE",
+                amount_u64_to_string(balance),
+                amount_u64_to_string(balance_after)
+            );
+            info!(
+                "  Stake: {} ORE -> {} ORE",
+                amount_u64_to_string(stake_before),
+                amount_u64_to_string(stake_after)
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Miner {
+    /// Stakes the ORE held in the signer's token account if it meets the minimum amount.
+    /// Returns the amount staked.
+    pub async fn auto_stake(
+        &self,
+        signer: &dyn Signer,
+        fee_payer: &dyn Signer,
+        min_amount: u64,
+    ) -> Option<u64> {
+        // Get token account
+        let sender = spl_associated_token_account::get_associated_token_address(
+            &signer.pubkey(),
+            &ore_api::consts::MINT_ADDRESS,
+        );
+        let Ok(balance) = self.rpc_client.get_token_account_balance(&sender).await else {
+            // Skip if the token account does not exist
+            return None;
+        };
+        let amount = u64::from_str(balance.amount.as_str()).ok()?;
+        if amount.eq(&0) || amount.lt(&min_amount) {
+            return None;
+        }
+
+        // Send tx
+        let ix = ore_api::instruction::stake(signer.pubkey(), sender, amount);
+        self.send_and_confirm_with_signer(
+            signer,
+            fee_payer,
+            &[ix],
+            self.compute_budget(CU_LIMIT_CLAIM),
+            false,
+            self.retry_policy,
+        )
+        .await
+        .ok()
+        .map(|_| amount)
     }
 }
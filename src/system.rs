@@ -0,0 +1,204 @@
+use std::{fs, path::PathBuf};
+
+use systemstat::{Platform, System};
+use thread_priority::{set_current_thread_priority, ThreadPriority};
+use tracing::warn;
+
+use crate::args::{CpuGovernor, ThreadPriorityLevel};
+
+/// Directory holding one `cpuN` entry per logical cpu.
+const CPU_SYSFS_DIR: &str = "/sys/devices/system/cpu";
+
+pub struct SystemStats {
+    pub cpu_temp: Option<f32>,
+    pub load_avg_1m: Option<f32>,
+    pub load_avg_5m: Option<f32>,
+    pub load_avg_15m: Option<f32>,
+    pub rss_mb: Option<f64>,
+}
+
+/// Sets the scheduling priority of the current thread. Uses SetThreadPriority on Windows and
+/// the thread's niceness on Unix.
+pub fn set_thread_priority(level: ThreadPriorityLevel) -> Result<(), String> {
+    let priority = match level {
+        ThreadPriorityLevel::High => ThreadPriority::Max,
+        ThreadPriorityLevel::Normal => return Ok(()),
+        ThreadPriorityLevel::Low => ThreadPriority::Min,
+    };
+    set_current_thread_priority(priority).map_err(|err| format!("{:?}", err))
+}
+
+/// Frequency scaling governors as they were before mining, restored when dropped.
+pub struct CpuGovernorGuard {
+    original: Vec<(PathBuf, String)>,
+}
+
+impl CpuGovernorGuard {
+    /// Writes the original governor back to every cpu that was changed.
+    pub fn restore(mut self) -> Result<(), String> {
+        restore_governors(std::mem::take(&mut self.original))
+    }
+}
+
+impl Drop for CpuGovernorGuard {
+    fn drop(&mut self) {
+        if let Err(err) = restore_governors(std::mem::take(&mut self.original)) {
+            warn!("Failed to restore the CPU governor: {}", err);
+        }
+    }
+}
+
+/// Sets the frequency scaling governor of every cpu, remembering each one's current governor
+/// so it can be restored. Undoes any cpus already changed if one fails.
+pub fn set_cpu_governor(governor: CpuGovernor) -> Result<CpuGovernorGuard, String> {
+    let paths = scaling_governor_paths()?;
+    let mut guard = CpuGovernorGuard {
+        original: Vec::with_capacity(paths.len()),
+    };
+    for path in paths {
+        let current = fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        fs::write(&path, governor.to_string()).map_err(|err| {
+            format!(
+                "Failed to write {} (are you running as root?): {}",
+                path.display(),
+                err
+            )
+        })?;
+        guard.original.push((path, current.trim().to_string()));
+    }
+    Ok(guard)
+}
+
+fn restore_governors(original: Vec<(PathBuf, String)>) -> Result<(), String> {
+    let failed: Vec<String> = original
+        .into_iter()
+        .filter_map(|(path, governor)| {
+            fs::write(&path, governor)
+                .err()
+                .map(|err| format!("{}: {}", path.display(), err))
+        })
+        .collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(failed.join(", "))
+    }
+}
+
+/// Returns the scaling governor file of every cpu that has one.
+fn scaling_governor_paths() -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(CPU_SYSFS_DIR)
+        .map_err(|err| format!("Failed to read {}: {}", CPU_SYSFS_DIR, err))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .map_or(false, |index| {
+                    !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())
+                })
+        })
+        .map(|entry| entry.path().join("cpufreq/scaling_governor"))
+        .filter(|path| path.exists())
+        .collect();
+    if paths.is_empty() {
+        return Err("No cpu frequency scaling governors found".to_string());
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+pub fn get_system_stats() -> SystemStats {
+    let sys = System::new();
+    let load_avg = sys.load_average().ok();
+    SystemStats {
+        cpu_temp: sys.cpu_temp().ok(),
+        load_avg_1m: load_avg.as_ref().map(|l| l.one),
+        load_avg_5m: load_avg.as_ref().map(|l| l.five),
+        load_avg_15m: load_avg.as_ref().map(|l| l.fifteen),
+        rss_mb: get_rss_mb(),
+    }
+}
+
+/// Returns the resident set size of this process in megabytes.
+fn get_rss_mb() -> Option<f64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut sys = sysinfo::System::new();
+    sys.refresh_process(pid);
+    sys.process(pid)
+        .map(|process| process.memory() as f64 / (1024.0 * 1024.0))
+}
+
+/// Degrees below the max temperature the cpu must cool to before threads are restored.
+const THERMAL_HYSTERESIS: f32 = 5.0;
+
+pub struct ThermalThrottle {
+    max_temp: f32,
+    max_threads: u64,
+    threads: u64,
+}
+
+impl ThermalThrottle {
+    pub fn new(max_temp: f32, max_threads: u64) -> Self {
+        Self {
+            max_temp,
+            max_threads,
+            threads: max_threads,
+        }
+    }
+
+    /// Returns the number of threads to mine with, given the current cpu temperature. Each
+    /// update above the max temperature halves the threads again, down to 1, for as long as
+    /// the cpu stays hot. All threads are restored once it cools below the hysteresis band.
+    pub fn update(&mut self, cpu_temp: Option<f32>) -> u64 {
+        if let Some(cpu_temp) = cpu_temp {
+            if cpu_temp > self.max_temp {
+                self.threads = self.threads.saturating_div(2).max(1);
+            } else if cpu_temp < self.max_temp - THERMAL_HYSTERESIS {
+                self.threads = self.max_threads;
+            }
+        }
+        self.threads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thermal_throttle_halves_down_to_one_thread() {
+        let mut throttle = ThermalThrottle::new(80.0, 8);
+        assert_eq!(throttle.update(Some(85.0)), 4);
+        assert_eq!(throttle.update(Some(85.0)), 2);
+        assert_eq!(throttle.update(Some(85.0)), 1);
+        assert_eq!(throttle.update(Some(85.0)), 1);
+    }
+
+    #[test]
+    fn thermal_throttle_holds_within_hysteresis_band() {
+        let mut throttle = ThermalThrottle::new(80.0, 8);
+        throttle.update(Some(85.0));
+        assert_eq!(throttle.update(Some(80.0)), 4);
+        assert_eq!(throttle.update(Some(75.0)), 4);
+    }
+
+    #[test]
+    fn thermal_throttle_restores_threads_once_cool() {
+        let mut throttle = ThermalThrottle::new(80.0, 8);
+        throttle.update(Some(85.0));
+        throttle.update(Some(85.0));
+        assert_eq!(throttle.update(Some(70.0)), 8);
+    }
+
+    #[test]
+    fn thermal_throttle_ignores_missing_readings() {
+        let mut throttle = ThermalThrottle::new(80.0, 8);
+        assert_eq!(throttle.update(None), 8);
+        throttle.update(Some(85.0));
+        assert_eq!(throttle.update(None), 4);
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// A pool of hashing threads dedicated to one wallet, starting at its own core so wallets
+/// do not compete for the same cores.
+pub struct WalletThreadPool {
+    pub pool: ThreadPool,
+    pub first_core: usize,
+    alive: Arc<AtomicUsize>,
+}
+
+impl WalletThreadPool {
+    pub fn new(name: &str, first_core: usize, threads: u64) -> Result<Self, ThreadPoolBuildError> {
+        let alive = Arc::new(AtomicUsize::new(0));
+        let name = name.to_string();
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads.max(1) as usize)
+            .thread_name(move |i| format!("{}-{}", name, i))
+            .start_handler({
+                let alive = alive.clone();
+                move |_| {
+                    alive.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .exit_handler({
+                let alive = alive.clone();
+                move |_| {
+                    alive.fetch_sub(1, Ordering::Relaxed);
+                }
+            })
+            .build()?;
+        Ok(Self {
+            pool,
+            first_core,
+            alive,
+        })
+    }
+
+    /// The number of threads the pool was built with.
+    pub fn threads(&self) -> u64 {
+        self.pool.current_num_threads() as u64
+    }
+
+    /// The number of pool threads currently running.
+    pub fn alive(&self) -> u64 {
+        self.alive.load(Ordering::Relaxed) as u64
+    }
+}
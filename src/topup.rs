@@ -0,0 +1,91 @@
+use serde_json::json;
+use solana_program::{
+    native_token::{lamports_to_sol, sol_to_lamports},
+    pubkey::Pubkey,
+    system_instruction,
+};
+use solana_sdk::{
+    signature::{read_keypair_file, Keypair, Signer},
+    signer::keypair::keypair_from_seed_phrase_and_passphrase,
+};
+use tracing::info;
+
+use crate::{cu_limits::CU_LIMIT_TRANSFER, output::print_json_event, Miner};
+
+/// Path that prompts for the funding wallet's seed phrase and passphrase instead of reading a file.
+const PROMPT_PATH: &str = "prompt://";
+
+/// Reads the funding wallet from a keypair file, or from a seed phrase and optional passphrase
+/// entered at the terminal if the path is `prompt://`.
+pub fn read_funding_keypair(path: &str) -> Result<Keypair, String> {
+    if path.ne(PROMPT_PATH) {
+        return read_keypair_file(path)
+            .map_err(|err| format!("Failed to read funding keypair {}: {}", path, err));
+    }
+    let seed_phrase = rpassword::prompt_password("Funding wallet seed phrase: ")
+        .map_err(|err| err.to_string())?;
+    let passphrase = rpassword::prompt_password("Funding wallet passphrase (empty for none): ")
+        .map_err(|err| err.to_string())?;
+    keypair_from_seed_phrase_and_passphrase(seed_phrase.trim(), &passphrase)
+        .map_err(|err| format!("Invalid funding seed phrase: {}", err))
+}
+
+impl Miner {
+    /// Transfers SOL from the funding wallet to the fee payer and waits for it to confirm.
+    /// Returns whether the transfer landed.
+    pub async fn auto_topup(&self, funding: &Keypair, fee_payer: &Pubkey, amount: u64) -> bool {
+        // Leave the funding wallet enough to pay for the transfer itself
+        let funding_balance = self
+            .rpc_client
+            .get_balance(&funding.pubkey())
+            .await
+            .unwrap_or(0);
+        let required = amount.saturating_add(sol_to_lamports(self.min_sol_balance));
+        if funding_balance.le(&required) {
+            self.print_warning(&format!(
+                "Funding wallet {} has {} SOL, not enough to top up {} SOL",
+                funding.pubkey(),
+                lamports_to_sol(funding_balance),
+                lamports_to_sol(amount)
+            ));
+            return false;
+        }
+
+        // Transfer
+        if self.is_json() {
+            print_json_event(
+                "topup",
+                json!({
+                    "wallet": fee_payer.to_string(),
+                    "funding_wallet": funding.pubkey().to_string(),
+                    "amount": lamports_to_sol(amount),
+                }),
+            );
+        } else {
+            info!(
+                "Topping up {} with {} SOL from {}",
+                fee_payer,
+                lamports_to_sol(amount),
+                funding.pubkey()
+            );
+        }
+        let ix = system_instruction::transfer(&funding.pubkey(), fee_payer, amount);
+        match self
+            .send_and_confirm_with_signer(
+                funding,
+                funding,
+                &[ix],
+                self.compute_budget(CU_LIMIT_TRANSFER),
+                false,
+                self.retry_policy,
+            )
+            .await
+        {
+            Ok(_) => true,
+            Err(err) => {
+                self.print_warning(&format!("Top up failed: {}", err));
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use colored::*;
+use solana_client::{nonblocking::tpu_client::TpuClient, tpu_client::TpuClientConfig};
+use solana_program::instruction::Instruction;
+use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::{error::MineError, send_and_confirm::ComputeBudget, tui, Miner};
+
+const TPU_RETRIES: usize = 5;
+const CONFIRM_RETRIES: usize = 8;
+const CONFIRM_DELAY: u64 = 500;
+
+pub type QuicTpuClient = TpuClient<QuicPool, QuicConnectionManager, QuicConfig>;
+
+impl Miner {
+    /// Connects to the TPU ports of the upcoming leaders, following the leader schedule
+    /// over the rpc websocket.
+    pub async fn tpu_client(&self) -> Result<QuicTpuClient, String> {
+        let websocket_url =
+            solana_cli_config::Config::compute_websocket_url(&self.rpc_client.url());
+        TpuClient::new(
+            "ore-cli",
+            self.rpc_client.clone(),
+            &websocket_url,
+            TpuClientConfig::default(),
+        )
+        .await
+        .map_err(|err| err.to_string())
+    }
+
+    /// Sends a transaction straight to the leaders' TPU ports, falling back to rpc if it
+    /// has not landed after a few attempts.
+    pub async fn send_and_confirm_tpu(
+        &self,
+        tpu_client: &QuicTpuClient,
+        signer: &dyn Signer,
+        fee_payer: &dyn Signer,
+        ixs: &[Instruction],
+        compute_budget: ComputeBudget,
+    ) -> Result<Signature, MineError> {
+        // Set compute budget
+        let cu_limit = compute_budget.cu_limit();
+        let cu_price = match self.capped_compute_unit_price(&compute_budget).await? {
+            Some(fee) => fee,
+            None => self.static_compute_unit_price(),
+        };
+        let mut final_ixs = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(cu_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(cu_price),
+        ];
+        final_ixs.extend_from_slice(ixs);
+        let signers: Vec<&dyn Signer> = if signer.pubkey() == fee_payer.pubkey() {
+            vec![signer]
+        } else {
+            vec![signer, fee_payer]
+        };
+
+        // Submit tx
+        let progress_bar = tui::new_progress_bar();
+        for attempt in 0..TPU_RETRIES {
+            progress_bar.set_message(format!(
+                "Submitting transaction via TPU... (attempt {})",
+                attempt
+            ));
+            let Ok(hash) = self.rpc_client.get_latest_blockhash().await else {
+                continue;
+            };
+            let mut tx = Transaction::new_with_payer(&final_ixs, Some(&fee_payer.pubkey()));
+            tx.sign(&signers, hash);
+            if !tpu_client.send_transaction(&tx).await {
+                continue;
+            }
+
+            // Confirm tx
+            let sig = tx.signatures[0];
+            for _ in 0..CONFIRM_RETRIES {
+                tokio::time::sleep(Duration::from_millis(CONFIRM_DELAY)).await;
+                let Ok(statuses) = self.rpc_client.get_signature_statuses(&[sig]).await else {
+                    continue;
+                };
+                if let Some(Some(status)) = statuses.value.first() {
+                    if let Some(err) = &status.err {
+                        progress_bar.finish_with_message(format!(
+                            "{}: {}",
+                            "ERROR".bold().red(),
+                            err
+                        ));
+                        return Err(err.clone().into());
+                    }
+                    if status.satisfies_commitment(self.rpc_client.commitment()) {
+                        progress_bar.finish_with_message(format!(
+                            "{} {}",
+                            "OK".bold().green(),
+                            sig
+                        ));
+                        return Ok(sig);
+                    }
+                }
+            }
+        }
+        progress_bar.finish_and_clear();
+
+        // Fall back to rpc
+        self.print_warning("TPU submission did not land, retrying over rpc");
+        self.send_and_confirm_with_signer(
+            signer,
+            fee_payer,
+            ixs,
+            compute_budget,
+            false,
+            self.retry_policy,
+        )
+        .await
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::{self, Stdout},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Row, Sparkline, Table},
+    Frame, Terminal,
+};
+use serde_json::Value;
+use solana_rpc_client::spinner;
+
+use crate::output;
+
+const TICK: Duration = Duration::from_secs(1);
+const LOG_CAPACITY: usize = 500;
+const SPARKLINE_CAPACITY: usize = 120;
+const MAX_CPU_TEMP: f64 = 100.0;
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static STATE: OnceLock<Mutex<TuiState>> = OnceLock::new();
+
+/// Everything shown in the dashboard, fed by the miner's events.
+#[derive(Default)]
+struct TuiState {
+    log: VecDeque<String>,
+    scroll: usize,
+    hashrates: VecDeque<u64>,
+    threads: BTreeMap<usize, u32>,
+    pass: u64,
+    difficulty: u64,
+    hashrate: u64,
+    sol_balance: f64,
+    staked_balance: f64,
+    session_ore: f64,
+    session_sol: f64,
+    cpu_temp: Option<f64>,
+}
+
+/// A full-screen dashboard of live mining stats. Restores the terminal when stopped.
+pub struct Tui {
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Returns whether the dashboard currently owns the terminal.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Creates a spinner, hidden while the dashboard owns the terminal. Once a session starts, the
+/// spinner is prefixed with the start of its id.
+pub fn new_progress_bar() -> ProgressBar {
+    let progress_bar = spinner::new_progress_bar();
+    if let Some(session_id) = output::session_id() {
+        progress_bar.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} [{prefix}] {wide_msg}")
+                .unwrap(),
+        );
+        progress_bar.set_prefix(session_id.chars().take(8).collect::<String>());
+    }
+    if is_active() {
+        progress_bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    progress_bar
+}
+
+/// Feeds an event printed by the miner to the dashboard.
+pub fn record_event(event: &str, fields: &Value) {
+    let mut state = state().lock().unwrap();
+    match event {
+        "pass" => {
+            state.pass = fields["pass"].as_u64().unwrap_or_default();
+            state.difficulty = fields["difficulty"].as_u64().unwrap_or_default();
+            state.hashrate = fields["hashrate"].as_u64().unwrap_or_default();
+            state.sol_balance = fields["sol_balance"].as_f64().unwrap_or_default();
+            state.session_ore = fields["session_ore_mined"].as_f64().unwrap_or_default();
+            state.session_sol = fields["session_sol_used"].as_f64().unwrap_or_default();
+            state.cpu_temp = fields["cpu_temp"].as_f64();
+            let hashrate = state.hashrate;
+            state.hashrates.push_back(hashrate);
+            while state.hashrates.len() > SPARKLINE_CAPACITY {
+                state.hashrates.pop_front();
+            }
+            state.threads.clear();
+            let line = format!(
+                "Pass {}: difficulty {}, {} ORE, {} SOL",
+                state.pass,
+                state.difficulty,
+                fields["ore_mined_pass"].as_f64().unwrap_or_default(),
+                fields["sol_cost_pass"].as_f64().unwrap_or_default(),
+            );
+            push_log(&mut state, line);
+        }
+        "balance" => {
+            state.staked_balance = fields["staked_balance"].as_f64().unwrap_or_default();
+        }
+        _ => {
+            let details = match fields.get("message") {
+                Some(Value::String(message)) => message.clone(),
+                _ => {
+                    let mut fields = fields.clone();
+                    if let Some(fields) = fields.as_object_mut() {
+                        fields.remove("event");
+                        fields.remove("timestamp_utc");
+                    }
+                    fields.to_string()
+                }
+            };
+            push_log(&mut state, format!("{}: {}", event, details));
+        }
+    }
+}
+
+/// Records the best difficulty a hashing thread has found this pass.
+pub fn record_thread(index: usize, best_difficulty: u32) {
+    if is_active() {
+        state()
+            .lock()
+            .unwrap()
+            .threads
+            .insert(index, best_difficulty);
+    }
+}
+
+impl Tui {
+    /// Takes over the terminal and redraws the dashboard every second. Pressing `q` sets the
+    /// shutdown flag, so mining stops after the current pass.
+    pub fn start(shutdown: Arc<AtomicBool>) -> io::Result<Self> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        ACTIVE.store(true, Ordering::Relaxed);
+        let handle = std::thread::spawn(move || {
+            while is_active() {
+                let tick = Instant::now();
+                draw(&mut terminal).ok();
+                while tick.elapsed().lt(&TICK) {
+                    let timeout = TICK.saturating_sub(tick.elapsed());
+                    if !event::poll(timeout).unwrap_or(false) {
+                        break;
+                    }
+                    if let Ok(Event::Key(key)) = event::read() {
+                        if key.kind.ne(&KeyEventKind::Press) {
+                            continue;
+                        }
+                        let mut state = state().lock().unwrap();
+                        match key.code {
+                            KeyCode::Char('q') => request_shutdown(&mut state, &shutdown),
+                            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                request_shutdown(&mut state, &shutdown)
+                            }
+                            KeyCode::Up => {
+                                state.scroll = (state.scroll + 1).min(state.log.len());
+                            }
+                            KeyCode::Down => state.scroll = state.scroll.saturating_sub(1),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            handle: Some(handle),
+        })
+    }
+
+    /// Hands the terminal back.
+    pub fn stop(mut self) {
+        self.restore();
+    }
+
+    fn restore(&mut self) {
+        ACTIVE.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+            disable_raw_mode().ok();
+            execute!(io::stdout(), LeaveAlternateScreen).ok();
+        }
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+fn state() -> &'static Mutex<TuiState> {
+    STATE.get_or_init(Default::default)
+}
+
+fn push_log(state: &mut TuiState, line: String) {
+    state.log.push_back(line);
+    while state.log.len() > LOG_CAPACITY {
+        state.log.pop_front();
+    }
+}
+
+fn request_shutdown(state: &mut TuiState, shutdown: &AtomicBool) {
+    shutdown.store(true, Ordering::Relaxed);
+    push_log(state, "Shutting down after the current pass...".into());
+}
+
+fn draw(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+    let state = state().lock().unwrap();
+    terminal.draw(|frame| render(frame, &state))?;
+    Ok(())
+}
+
+fn render(frame: &mut Frame, state: &TuiState) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[1]);
+    let panes = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(7),
+            Constraint::Length(3),
+            Constraint::Min(0),
+        ])
+        .split(columns[1]);
+
+    // Stats bar
+    let stats = format!(
+        "Pass {} | Difficulty {} | {} H/sec | {} SOL | Stake {} ORE | Session {} ORE, {} SOL",
+        state.pass,
+        state.difficulty,
+        state.hashrate,
+        state.sol_balance,
+        state.staked_balance,
+        state.session_ore,
+        state.session_sol,
+    );
+    frame.render_widget(
+        Paragraph::new(stats).block(Block::default().borders(Borders::ALL).title("ORE")),
+        rows[0],
+    );
+
+    // Pass log, scrolled up from the latest line
+    let height = columns[0].height.saturating_sub(2) as usize;
+    let end = state.log.len().saturating_sub(state.scroll);
+    let items: Vec<ListItem> = state
+        .log
+        .iter()
+        .take(end)
+        .skip(end.saturating_sub(height))
+        .map(|line| ListItem::new(line.as_str()))
+        .collect();
+    frame.render_widget(
+        List::new(items).block(Block::default().borders(Borders::ALL).title("Passes")),
+        columns[0],
+    );
+
+    // Hashrate sparkline
+    let hashrates: Vec<u64> = state.hashrates.iter().copied().collect();
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title("H/sec"))
+            .style(Style::default().fg(Color::Green))
+            .data(&hashrates),
+        panes[0],
+    );
+
+    // CPU temperature
+    let (ratio, label) = match state.cpu_temp {
+        Some(temp) => (
+            (temp / MAX_CPU_TEMP).clamp(0.0, 1.0),
+            format!("{:.1}°C", temp),
+        ),
+        None => (0.0, "n/a".to_string()),
+    };
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title("CPU temp"))
+            .gauge_style(Style::default().fg(Color::Red))
+            .ratio(ratio)
+            .label(label),
+        panes[1],
+    );
+
+    // Thread activity
+    let thread_rows: Vec<Row> = state
+        .threads
+        .iter()
+        .map(|(index, difficulty)| Row::new(vec![index.to_string(), difficulty.to_string()]))
+        .collect();
+    frame.render_widget(
+        Table::new(thread_rows, [Constraint::Length(8), Constraint::Min(0)])
+            .header(Row::new(vec!["Thread", "Best difficulty"]))
+            .block(Block::default().borders(Borders::ALL).title("Threads")),
+        panes[2],
+    );
+
+    frame.render_widget(
+        Paragraph::new("q: quit after this pass  ↑/↓: scroll"),
+        rows[2],
+    );
+}
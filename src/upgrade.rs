@@ -1,24 +1,25 @@
 use colored::*;
 use solana_sdk::{pubkey::Pubkey, signer::Signer};
 use spl_token::amount_to_ui_amount;
+use tracing::info;
 
 use crate::{
-    cu_limits::CU_LIMIT_UPGRADE,
-    send_and_confirm::ComputeBudget,
+    cu_limits::{CU_LIMIT_MAX, CU_LIMIT_UPGRADE},
+    error::MineError,
     utils::{amount_f64_to_u64_v1, ask_confirm},
     Miner, UpgradeArgs,
 };
 
 impl Miner {
-    pub async fn upgrade(&self, args: UpgradeArgs) {
-        let signer = &self.signer();
-        let beneficiary = self.get_or_initialize_ata().await;
-        let (sender, sender_balance) = self.get_ata_v1().await;
+    pub async fn upgrade(&self, args: UpgradeArgs) -> Result<(), MineError> {
+        let signer = self.try_signer()?;
+        let beneficiary = self.get_or_initialize_ata(signer.as_ref()).await?;
+        let (sender, sender_balance) = self.get_ata_v1(signer.as_ref()).await?;
 
         let amount_f64 = match args.amount {
             Some(f64) => f64,
             None => {
-                println!(
+                info!(
                     "Defaulting to max amount of v1 Ore token in wallet: {}",
                     sender_balance
                 );
@@ -35,25 +36,23 @@ impl Miner {
             )
             .as_str(),
         ) {
-            return;
+            return Ok(());
         }
 
         let ix = ore_api::instruction::upgrade(signer.pubkey(), beneficiary, sender, amount);
-        match self
-            .send_and_confirm(&[ix], ComputeBudget::Fixed(CU_LIMIT_UPGRADE), false)
-            .await
-        {
-            Ok(_sig) => {}
-            Err(err) => {
-                println!("error: {}", err);
-            }
-        }
+        self.send_and_confirm(
+            &[ix],
+            self.compute_budget(CU_LIMIT_UPGRADE),
+            false,
+            self.retry_policy,
+        )
+        .await?;
+        Ok(())
     }
 
     // asserts that token account exists and gets balance
-    async fn get_ata_v1(&self) -> (Pubkey, f64) {
+    async fn get_ata_v1(&self, signer: &dyn Signer) -> Result<(Pubkey, f64), MineError> {
         // Initialize client.
-        let signer = self.signer();
         let client = self.rpc_client.clone();
 
         // Derive assoicated token address (for v1 account)
@@ -63,31 +62,29 @@ impl Miner {
         );
 
         // Get token account balance
-        let balance = match client.get_token_account(&token_account_pubkey_v1).await {
-            Ok(None) => {
-                panic!("v1 token account doesn't exist")
+        let balance = match client.get_token_account(&token_account_pubkey_v1).await? {
+            None => {
+                return Err(MineError::InvalidArgs(
+                    "v1 token account doesn't exist".to_string(),
+                ))
             }
-            Ok(Some(token_account)) => match token_account.token_amount.ui_amount {
+            Some(token_account) => match token_account.token_amount.ui_amount {
                 Some(ui_amount) => ui_amount,
                 None => {
-                    panic!(
+                    return Err(MineError::Unknown(format!(
                         "Error parsing token account UI amount: {}",
                         token_account.token_amount.amount
-                    )
+                    )))
                 }
             },
-            Err(err) => {
-                panic!("Error fetching token account: {}", err)
-            }
         };
 
         // Return v1 token account address
-        (token_account_pubkey_v1, balance)
+        Ok((token_account_pubkey_v1, balance))
     }
 
-    async fn get_or_initialize_ata(&self) -> Pubkey {
+    async fn get_or_initialize_ata(&self, signer: &dyn Signer) -> Result<Pubkey, MineError> {
         // Initialize client
-        let signer = self.signer();
         let client = self.rpc_client.clone();
 
         // Derive assoicated token address (ata)
@@ -98,19 +95,23 @@ impl Miner {
 
         // Check if ata already exists or init
         if let Err(_err) = client.get_token_account(&token_account_pubkey).await {
-            println!("Initializing v2 token account...");
+            info!("Initializing v2 token account...");
             let ix = spl_associated_token_account::instruction::create_associated_token_account(
                 &signer.pubkey(),
                 &signer.pubkey(),
                 &ore_api::consts::MINT_ADDRESS,
                 &spl_token::id(),
             );
-            self.send_and_confirm(&[ix], ComputeBudget::Dynamic, false)
-                .await
-                .ok();
+            self.send_and_confirm(
+                &[ix],
+                self.compute_budget(CU_LIMIT_MAX),
+                false,
+                self.retry_policy,
+            )
+            .await?;
         }
 
         // Return token account address
-        token_account_pubkey
+        Ok(token_account_pubkey)
     }
 }
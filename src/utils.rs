@@ -10,44 +10,57 @@ use ore_api::{
 use ore_utils::AccountDeserialize;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::{pubkey::Pubkey, sysvar};
-use solana_sdk::clock::Clock;
+use solana_sdk::{clock::Clock, signature::Keypair};
 use spl_associated_token_account::get_associated_token_address;
 
-pub async fn _get_treasury(client: &RpcClient) -> Treasury {
-    let data = client
-        .get_account_data(&TREASURY_ADDRESS)
-        .await
-        .expect("Failed to get treasury account");
-    *Treasury::try_from_bytes(&data).expect("Failed to parse treasury account")
+use crate::error::MineError;
+
+pub async fn _get_treasury(client: &RpcClient) -> Result<Treasury, MineError> {
+    let data = client.get_account_data(&TREASURY_ADDRESS).await?;
+    Treasury::try_from_bytes(&data)
+        .copied()
+        .map_err(|_| MineError::Unknown("Failed to parse treasury account".to_string()))
 }
 
-pub async fn get_config(client: &RpcClient) -> Config {
-    let data = client
-        .get_account_data(&CONFIG_ADDRESS)
-        .await
-        .expect("Failed to get config account");
-    *Config::try_from_bytes(&data).expect("Failed to parse config account")
+pub async fn get_config(client: &RpcClient) -> Result<Config, MineError> {
+    let data = client.get_account_data(&CONFIG_ADDRESS).await?;
+    Config::try_from_bytes(&data)
+        .copied()
+        .map_err(|_| MineError::Unknown("Failed to parse config account".to_string()))
 }
 
-pub async fn get_proof_with_authority(client: &RpcClient, authority: Pubkey) -> Proof {
+pub async fn get_proof_with_authority(
+    client: &RpcClient,
+    authority: Pubkey,
+) -> Result<Proof, MineError> {
     let proof_address = proof_pubkey(authority);
     get_proof(client, proof_address).await
 }
 
-pub async fn get_proof(client: &RpcClient, address: Pubkey) -> Proof {
-    let data = client
-        .get_account_data(&address)
-        .await
-        .expect("Failed to get miner account");
-    *Proof::try_from_bytes(&data).expect("Failed to parse miner account")
+pub async fn get_proof(client: &RpcClient, address: Pubkey) -> Result<Proof, MineError> {
+    let account = client
+        .get_account_with_commitment(&address, client.commitment())
+        .await?
+        .value
+        .ok_or(MineError::ProofNotFound(address))?;
+    Proof::try_from_bytes(&account.data)
+        .copied()
+        .map_err(|_| MineError::ProofNotFound(address))
 }
 
-pub async fn get_clock(client: &RpcClient) -> Clock {
+/// Returns the proof of the authority, or None if it has not been registered.
+pub async fn try_get_proof_with_authority(client: &RpcClient, authority: Pubkey) -> Option<Proof> {
     let data = client
-        .get_account_data(&sysvar::clock::ID)
+        .get_account_data(&proof_pubkey(authority))
         .await
-        .expect("Failed to get miner account");
-    bincode::deserialize::<Clock>(&data).expect("Failed to deserialize clock")
+        .ok()?;
+    Proof::try_from_bytes(&data).ok().copied()
+}
+
+pub async fn get_clock(client: &RpcClient) -> Result<Clock, MineError> {
+    let data = client.get_account_data(&sysvar::clock::ID).await?;
+    bincode::deserialize::<Clock>(&data)
+        .map_err(|_| MineError::Unknown("Failed to deserialize clock".to_string()))
 }
 
 pub fn amount_u64_to_string(amount: u64) -> String {
@@ -67,6 +80,7 @@ pub fn amount_f64_to_u64_v1(amount: f64) -> u64 {
 }
 
 pub fn ask_confirm(question: &str) -> bool {
+    // Prompt on stdout directly, so the question is shown at any log level
     println!("{}", question);
     loop {
         let mut input = [0];
@@ -88,3 +102,48 @@ pub fn proof_pubkey(authority: Pubkey) -> Pubkey {
 pub fn treasury_tokens_pubkey() -> Pubkey {
     get_associated_token_address(&TREASURY_ADDRESS, &MINT_ADDRESS)
 }
+
+/// Reads a base58 or JSON byte array encoded keypair from an environment variable,
+/// then removes the variable so the secret does not linger in the environment.
+pub fn read_keypair_env(name: &str) -> Result<Keypair, String> {
+    let value =
+        std::env::var(name).map_err(|_| format!("Environment variable `{}` is not set", name))?;
+    std::env::remove_var(name);
+    decode_keypair(&value).map_err(|err| format!("Invalid keypair in `{}`: {}", name, err))
+}
+
+/// Reads a base58 or JSON byte array encoded keypair from the output of a shell command,
+/// such as a password manager's cli. The command's stderr goes to the miner's stderr.
+pub fn read_keypair_command(command: &str) -> Result<Keypair, String> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let output = shell
+        .arg(command)
+        .stdin(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .map_err(|err| format!("Failed to run keypair command: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("Keypair command exited with {}", output.status));
+    }
+    let value = String::from_utf8(output.stdout)
+        .map_err(|_| "Keypair command printed invalid UTF-8".to_string())?;
+    decode_keypair(&value).map_err(|err| format!("Invalid keypair from command: {}", err))
+}
+
+/// Decodes a base58 keypair, falling back to a JSON byte array.
+fn decode_keypair(value: &str) -> Result<Keypair, String> {
+    let value = value.trim();
+    let bytes = match bs58::decode(value).into_vec() {
+        Ok(bytes) => bytes,
+        Err(_) => serde_json::from_str::<Vec<u8>>(value).map_err(|err| err.to_string())?,
+    };
+    Keypair::from_bytes(&bytes).map_err(|err| err.to_string())
+}
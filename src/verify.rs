@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use base64::Engine;
+use drillx::Solution;
+use ore_api::instruction::OreInstruction;
+use serde_json::json;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_program::instruction::InstructionError;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, signature::Signature, transaction::TransactionError,
+};
+use solana_transaction_status::{
+    UiReturnDataEncoding, UiTransactionEncoding, UiTransactionReturnData,
+};
+use tracing::info;
+
+use crate::{
+    args::VerifyArgs,
+    error::MineError,
+    output::print_json_event,
+    utils::{amount_u64_to_f64, amount_u64_to_string},
+    Miner,
+};
+
+/// Error code the program returns for a submission that lands after the epoch ended.
+const NEEDS_RESET: u32 = 0;
+
+/// Size of the difficulty, reward and timing fields of the mine event returned by the program.
+const MINE_EVENT_SIZE: usize = 24;
+
+impl Miner {
+    pub async fn verify(&self, args: VerifyArgs) -> Result<(), MineError> {
+        let signature = Signature::from_str(&args.signature)
+            .map_err(|_| MineError::InvalidArgs(format!("Invalid signature {}", args.signature)))?;
+
+        // Fetch transaction
+        let tx = self
+            .rpc_client
+            .get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+        let Some(transaction) = tx.transaction.transaction.decode() else {
+            return Err(MineError::Unknown(format!(
+                "Failed to decode transaction {}",
+                signature
+            )));
+        };
+        let Some(meta) = tx.transaction.meta else {
+            return Err(MineError::Unknown(format!(
+                "Transaction {} has no status",
+                signature
+            )));
+        };
+
+        // Find the mine instruction and the difficulty of its solution
+        let keys = transaction.message.static_account_keys();
+        let mine = transaction
+            .message
+            .instructions()
+            .iter()
+            .enumerate()
+            .find(|(_, ix)| {
+                keys.get(ix.program_id_index as usize)
+                    .map_or(false, |program_id| program_id.eq(&ore_api::ID))
+                    && ix.data.first().eq(&Some(&(OreInstruction::Mine as u8)))
+            });
+        let Some((mine_index, mine_ix)) = mine else {
+            return Err(MineError::InvalidArgs(format!(
+                "Transaction {} does not submit an ORE solution",
+                signature
+            )));
+        };
+        let Some(difficulty) = solution_difficulty(&mine_ix.data) else {
+            return Err(MineError::Unknown(format!(
+                "Failed to parse the solution in {}",
+                signature
+            )));
+        };
+
+        // Check the outcome
+        let (status, reason) = match &meta.err {
+            None => ("accepted", None),
+            Some(TransactionError::InstructionError(index, InstructionError::Custom(code)))
+                if (*index as usize).eq(&mine_index) && code.eq(&NEEDS_RESET) =>
+            {
+                (
+                    "epoch_reset",
+                    Some("The epoch had ended when the solution landed".to_string()),
+                )
+            }
+            Some(err) => ("rejected", Some(err.to_string())),
+        };
+
+        // Read the reward from the mine event the program returns, if any
+        let return_data: Option<UiTransactionReturnData> = meta.return_data.into();
+        let reward = return_data
+            .filter(|return_data| {
+                return_data.program_id.eq(&ore_api::ID.to_string())
+                    && return_data.data.1.eq(&UiReturnDataEncoding::Base64)
+            })
+            .and_then(|return_data| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(return_data.data.0)
+                    .ok()
+            })
+            .filter(|data| data.len().ge(&MINE_EVENT_SIZE))
+            .map(|data| u64::from_le_bytes(data[8..16].try_into().unwrap()));
+
+        if self.is_json() {
+            print_json_event(
+                "verify",
+                json!({
+                    "signature": signature.to_string(),
+                    "slot": tx.slot,
+                    "status": status,
+                    "reason": reason,
+                    "difficulty": difficulty,
+                    "reward": reward.map(amount_u64_to_f64),
+                }),
+            );
+            return Ok(());
+        }
+        info!("Signature: {}", signature);
+        info!("Slot: {}", tx.slot);
+        info!("Status: {}", status);
+        if let Some(reason) = reason {
+            info!("Reason: {}", reason);
+        }
+        info!("Difficulty: {}", difficulty);
+        match reward {
+            Some(reward) => info!("Reward: {} ORE", amount_u64_to_string(reward)),
+            None if meta.err.is_none() => {
+                info!("Reward: unknown (the program did not return a mine event)")
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// Returns the difficulty of the solution in a mine instruction's data.
+fn solution_difficulty(data: &[u8]) -> Option<u32> {
+    let digest: [u8; 16] = data.get(1..17)?.try_into().ok()?;
+    let nonce: [u8; 8] = data.get(17..25)?.try_into().ok()?;
+    Some(Solution::new(digest, nonce).to_hash().difficulty())
+}